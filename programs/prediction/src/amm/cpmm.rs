@@ -0,0 +1,159 @@
+//! Constant-product market maker for binary markets.
+//!
+//! The pool holds a reserve of each outcome and keeps their product
+//! `k = reserve_in * reserve_out` from decreasing. Collateral paid in is split
+//! into complete sets and added to both reserves before tokens leave, so
+//! buying `amount` of an outcome for cost `c` satisfies
+//!
+//! ```text
+//! (reserve_in + c - amount) * (reserve_out + c) >= reserve_in * reserve_out
+//! ```
+//!
+//! and selling reverses it by merging complete sets back out for proceeds `r`:
+//!
+//! ```text
+//! (reserve_in + amount - r) * (reserve_out - r) >= reserve_in * reserve_out
+//! ```
+//!
+//! `reserve_in` is always the reserve of the outcome being traded. The
+//! program mints those complete sets into the pool's token accounts on a buy
+//! and burns them on a sell, so the reserves are the pool's token balances.
+use super::{math::isqrt, PRICE_SCALE};
+
+fn invariant_holds(reserve_in: u128, reserve_out: u128, k: u128) -> Option<bool> {
+    Some(reserve_in.checked_mul(reserve_out)? >= k)
+}
+
+/// Collateral needed to buy `amount` of the `reserve_in` outcome.
+pub fn buy_cost(reserve_in: u64, reserve_out: u64, amount: u64) -> Option<u64> {
+    let (x, y, n) = (reserve_in as u128, reserve_out as u128, amount as u128);
+    let k = x.checked_mul(y)?;
+
+    // c^2 + (x + y - n) c - n y = 0, take the positive root
+    let b = x as i128 + y as i128 - n as i128;
+    let discriminant =
+        (b.checked_mul(b)? as u128).checked_add(n.checked_mul(y)?.checked_mul(4)?)?;
+    let mut cost = ((isqrt(discriminant) as i128 - b).max(0) as u128) / 2;
+
+    // round up until the invariant holds
+    while x + cost < n || !invariant_holds(x + cost - n, y + cost, k)? {
+        cost += 1;
+    }
+    u64::try_from(cost).ok()
+}
+
+/// Collateral returned for selling `amount` of the `reserve_in` outcome.
+pub fn sell_proceeds(reserve_in: u64, reserve_out: u64, amount: u64) -> Option<u64> {
+    let (x, y, n) = (reserve_in as u128, reserve_out as u128, amount as u128);
+    let k = x.checked_mul(y)?;
+
+    // r^2 - (x + y + n) r + n y = 0, take the smaller root
+    let b = x.checked_add(y)?.checked_add(n)?;
+    let discriminant = b
+        .checked_mul(b)?
+        .checked_sub(n.checked_mul(y)?.checked_mul(4)?)?;
+    let mut proceeds = (b - isqrt(discriminant)) / 2;
+
+    // round down until the invariant holds
+    while proceeds > 0 && (proceeds > y || !invariant_holds(x + n - proceeds, y - proceeds, k)?) {
+        proceeds -= 1;
+    }
+    u64::try_from(proceeds).ok()
+}
+
+/// Reserves after a trade of `amount` tokens against `collateral`.
+pub fn reserves_after(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount: u64,
+    collateral: u64,
+    is_buy: bool,
+) -> Option<(u64, u64)> {
+    if is_buy {
+        Some((
+            reserve_in.checked_add(collateral)?.checked_sub(amount)?,
+            reserve_out.checked_add(collateral)?,
+        ))
+    } else {
+        Some((
+            reserve_in.checked_add(amount)?.checked_sub(collateral)?,
+            reserve_out.checked_sub(collateral)?,
+        ))
+    }
+}
+
+/// Marginal price of the `reserve_in` outcome, scaled by `PRICE_SCALE`.
+pub fn price(reserve_in: u64, reserve_out: u64) -> Option<u64> {
+    let total = (reserve_in as u128).checked_add(reserve_out as u128)?;
+    if total == 0 {
+        return None;
+    }
+    u64::try_from((reserve_out as u128).checked_mul(PRICE_SCALE as u128)? / total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESERVES: [(u64, u64); 4] = [
+        (1_000_000_000, 1_000_000_000),
+        (3_000_000_000, 500_000_000),
+        (500_000_000, 3_000_000_000),
+        (7, 11),
+    ];
+
+    fn product(reserve_in: u64, reserve_out: u64) -> u128 {
+        reserve_in as u128 * reserve_out as u128
+    }
+
+    #[test]
+    fn buy_cost_is_the_smallest_cost_keeping_the_invariant() {
+        for (x, y) in RESERVES {
+            for amount in [1, 2, 5, x / 3, x / 2] {
+                let cost = buy_cost(x, y, amount).unwrap();
+                let (x_after, y_after) = reserves_after(x, y, amount, cost, true).unwrap();
+                assert!(product(x_after, y_after) >= product(x, y));
+                if cost > 0 && x + cost > amount {
+                    let (x_less, y_less) = reserves_after(x, y, amount, cost - 1, true).unwrap();
+                    assert!(product(x_less, y_less) < product(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sell_proceeds_are_the_largest_keeping_the_invariant() {
+        for (x, y) in RESERVES {
+            for amount in [1, 2, 5, x / 3, x * 2] {
+                let proceeds = sell_proceeds(x, y, amount).unwrap();
+                let (x_after, y_after) = reserves_after(x, y, amount, proceeds, false).unwrap();
+                assert!(product(x_after, y_after) >= product(x, y));
+                if proceeds < y {
+                    let (x_more, y_more) =
+                        reserves_after(x, y, amount, proceeds + 1, false).unwrap();
+                    assert!(product(x_more, y_more) < product(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_never_pays_the_trader() {
+        for (x, y) in RESERVES {
+            let amount = x / 4 + 1;
+            let cost = buy_cost(x, y, amount).unwrap();
+            let (x_after, y_after) = reserves_after(x, y, amount, cost, true).unwrap();
+            let proceeds = sell_proceeds(x_after, y_after, amount).unwrap();
+            assert!(proceeds <= cost);
+        }
+    }
+
+    #[test]
+    fn prices_sum_to_one() {
+        for (x, y) in RESERVES {
+            let sum = price(x, y).unwrap() + price(y, x).unwrap();
+            assert!((PRICE_SCALE - 1..=PRICE_SCALE).contains(&sum));
+        }
+        assert_eq!(price(0, 0), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Logarithmic market scoring rule.
+//!
+//! With `q` the quantity of each outcome sold and `b` the liquidity parameter,
+//! the cost function is
+//!
+//! ```text
+//! C(q) = b * ln(sum_i exp(q_i / b))
+//! ```
+//!
+//! and a trade moving the quantities from `q` to `q'` costs `C(q') - C(q)`.
+//! Prices are `exp(q_i / b) / sum_j exp(q_j / b)` and always sum to one.
+//! Starting from `q = 0`, the most the pool can lose once every winning token
+//! is paid one unit is `b * ln(n)` for `n` outcomes, which LP liquidity has
+//! to cover.
+use super::{
+    math::{exp_neg, ln, ONE},
+    PRICE_SCALE,
+};
+
+/// Quantity of each outcome with outcome `index` replaced by `quantity`.
+fn adjusted(quantities: &[u64], index: usize, quantity: u64) -> impl Iterator<Item = u64> + '_ {
+    quantities
+        .iter()
+        .enumerate()
+        .map(move |(i, &q)| if i == index { quantity } else { q })
+}
+
+/// Sum of `exp((q_i - max) / b)` scaled by `ONE`. Shifting by the largest
+/// quantity keeps every exponent non-positive.
+fn weight_sum(quantities: impl Iterator<Item = u64>, max: u64, b: u64) -> Option<u128> {
+    let mut sum = 0u128;
+    for quantity in quantities {
+        let exponent = ((max - quantity) as u128).checked_mul(ONE)? / b as u128;
+        sum = sum.checked_add(exp_neg(exponent))?;
+    }
+    Some(sum)
+}
+
+/// `C(q)` scaled by `ONE`, with outcome `index` moved to `quantity`.
+fn cost_adjusted(quantities: &[u64], b: u64, index: usize, quantity: u64) -> Option<u128> {
+    if b == 0 || index >= quantities.len() {
+        return None;
+    }
+    let max = adjusted(quantities, index, quantity).max()?;
+    let sum = weight_sum(adjusted(quantities, index, quantity), max, b)?;
+    (max as u128)
+        .checked_mul(ONE)?
+        .checked_add((b as u128).checked_mul(ln(sum)?)?)
+}
+
+/// `C(q)` scaled by `ONE`.
+pub fn cost(quantities: &[u64], b: u64) -> Option<u128> {
+    cost_adjusted(quantities, b, 0, *quantities.first()?)
+}
+
+/// Collateral needed to buy `amount` of outcome `index`.
+pub fn buy_cost(quantities: &[u64], index: usize, amount: u64, b: u64) -> Option<u64> {
+    let after = quantities.get(index)?.checked_add(amount)?;
+    let delta = cost_adjusted(quantities, b, index, after)?.checked_sub(cost(quantities, b)?)?;
+    u64::try_from(delta.div_ceil(ONE)).ok()
+}
+
+/// Collateral returned for selling `amount` of outcome `index`.
+pub fn sell_proceeds(quantities: &[u64], index: usize, amount: u64, b: u64) -> Option<u64> {
+    let after = quantities.get(index)?.checked_sub(amount)?;
+    let delta = cost(quantities, b)?.checked_sub(cost_adjusted(quantities, b, index, after)?)?;
+    u64::try_from(delta / ONE).ok()
+}
+
+/// Marginal price of outcome `index`, scaled by `PRICE_SCALE`.
+pub fn price(quantities: &[u64], index: usize, b: u64) -> Option<u64> {
+    if b == 0 {
+        return None;
+    }
+    let max = *quantities.iter().max()?;
+    let sum = weight_sum(quantities.iter().copied(), max, b)?;
+    let weight = exp_neg(((max - *quantities.get(index)?) as u128).checked_mul(ONE)? / b as u128);
+    u64::try_from(weight.checked_mul(PRICE_SCALE as u128)? / sum).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amm::math::ln;
+
+    const B: u64 = 1_000_000_000;
+
+    #[test]
+    fn initial_cost_is_the_worst_case_loss() {
+        // C(0) = b * ln(n)
+        for n in 2..=8u128 {
+            let quantities = vec![0u64; n as usize];
+            let expected = B as u128 * ln(n * ONE).unwrap();
+            let actual = cost(&quantities, B).unwrap();
+            assert!(actual.abs_diff(expected) <= ONE);
+        }
+    }
+
+    #[test]
+    fn prices_sum_to_one() {
+        for quantities in [vec![0, 0], vec![5 * B, 0, B], vec![B, 2 * B, 3 * B, 0]] {
+            let sum: u64 = (0..quantities.len())
+                .map(|index| price(&quantities, index, B).unwrap())
+                .sum();
+            assert!(sum.abs_diff(PRICE_SCALE) <= quantities.len() as u64);
+        }
+    }
+
+    #[test]
+    fn buy_rounds_up_and_sell_rounds_down() {
+        let quantities = [B, 3 * B, 0];
+        for index in 0..quantities.len() {
+            for amount in [1, 1_000, B / 2, 4 * B] {
+                let mut after = quantities;
+                after[index] += amount;
+                let exact = cost(&after, B).unwrap() - cost(&quantities, B).unwrap();
+                let cost_paid = buy_cost(&quantities, index, amount, B).unwrap() as u128;
+                assert!(cost_paid * ONE >= exact);
+                assert!(cost_paid * ONE < exact + ONE);
+
+                let proceeds = sell_proceeds(&after, index, amount, B).unwrap() as u128;
+                assert!(proceeds * ONE <= exact);
+                assert!(proceeds <= cost_paid);
+            }
+        }
+    }
+
+    #[test]
+    fn complete_set_costs_one_unit() {
+        // C(q + a) - C(q) = a when every outcome moves by a
+        let quantities = [B, 2 * B, 0];
+        let amount = 123_456_789u64;
+        let shifted: Vec<u64> = quantities.iter().map(|q| q + amount).collect();
+        let delta = cost(&shifted, B).unwrap() - cost(&quantities, B).unwrap();
+        assert!(delta.abs_diff(amount as u128 * ONE) <= ONE);
+        for index in 0..quantities.len() {
+            let before = price(&quantities, index, B).unwrap();
+            assert!(price(&shifted, index, B).unwrap().abs_diff(before) <= 1);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(buy_cost(&[0, 0], 0, 1, 0), None);
+        assert_eq!(buy_cost(&[0, 0], 2, 1, B), None);
+        assert_eq!(sell_proceeds(&[0, 0], 0, 1, B), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! Deterministic fixed-point helpers shared by the curves. Values are scaled
+//! by `ONE` and only use integer arithmetic from `core`.
+
+pub const ONE: u128 = 1_000_000_000_000_000_000;
+const LN_2: u128 = 693_147_180_559_945_309;
+
+/// Integer square root, rounded down.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// `e^(-x)` for a non-negative fixed-point `x`.
+pub fn exp_neg(x: u128) -> u128 {
+    // e^(-x) = 2^(-k) * e^(-r) with r in [0, ln 2)
+    let k = x / LN_2;
+    if k >= 128 {
+        return 0;
+    }
+    let r = x - k * LN_2;
+
+    // Taylor series of e^(-r), terms alternate and shrink below ln 2
+    let mut sum = ONE as i128;
+    let mut term = ONE as i128;
+    let mut n = 1i128;
+    while term != 0 {
+        term = -term * r as i128 / ONE as i128 / n;
+        sum += term;
+        n += 1;
+    }
+    (sum as u128) >> k
+}
+
+/// Natural logarithm of a fixed-point `x >= ONE`.
+pub fn ln(x: u128) -> Option<u128> {
+    if x < ONE {
+        return None;
+    }
+
+    // ln(x) = k * ln 2 + ln(y) with y in [1, 2)
+    let mut k = 0u128;
+    let mut y = x;
+    while y >= 2 * ONE {
+        y /= 2;
+        k += 1;
+    }
+
+    // ln(y) = 2 * atanh(t) with t = (y - 1) / (y + 1) <= 1/3
+    let t = (y - ONE).checked_mul(ONE)? / (y + ONE);
+    let t_squared = t * t / ONE;
+    let mut sum = 0u128;
+    let mut power = t;
+    let mut n = 1u128;
+    while power != 0 {
+        sum += power / n;
+        power = power * t_squared / ONE;
+        n += 2;
+    }
+
+    k.checked_mul(LN_2)?.checked_add(2 * sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: u128, expected: u128, tolerance: u128) {
+        let diff = actual.abs_diff(expected);
+        assert!(
+            diff <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn isqrt_rounds_down() {
+        for n in [
+            0u128,
+            1,
+            2,
+            3,
+            4,
+            15,
+            16,
+            17,
+            99,
+            100,
+            1 << 64,
+            u64::MAX as u128,
+        ] {
+            let root = isqrt(n);
+            assert!(root * root <= n);
+            assert!((root + 1) * (root + 1) > n);
+        }
+        let root = isqrt(u128::MAX);
+        assert_eq!(root, u64::MAX as u128);
+    }
+
+    #[test]
+    fn exp_neg_matches_known_values() {
+        assert_eq!(exp_neg(0), ONE);
+        // e^-1 and e^-10
+        assert_close(exp_neg(ONE), 367_879_441_171_442_321, 1_000);
+        assert_close(exp_neg(10 * ONE), 45_399_929_762_484, 1_000);
+        assert_eq!(exp_neg(200 * ONE), 0);
+    }
+
+    #[test]
+    fn exp_neg_is_decreasing() {
+        let mut previous = exp_neg(0);
+        for step in 1..200u128 {
+            let current = exp_neg(step * ONE / 20);
+            assert!(current <= previous);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn ln_matches_known_values() {
+        assert_eq!(ln(ONE), Some(0));
+        assert_close(ln(2 * ONE).unwrap(), LN_2, 1_000);
+        // ln(10) and ln(e)
+        assert_close(ln(10 * ONE).unwrap(), 2_302_585_092_994_045_684, 1_000);
+        assert_close(ln(2_718_281_828_459_045_235).unwrap(), ONE, 1_000);
+        assert_eq!(ln(ONE - 1), None);
+    }
+
+    #[test]
+    fn ln_inverts_exp_neg() {
+        for x in [ONE / 10, ONE, 3 * ONE, 20 * ONE] {
+            // ln(1 / e^-x) = x
+            let inverse = ONE * ONE / exp_neg(x);
+            assert_close(ln(inverse).unwrap(), x, ONE / 1_000_000_000);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! Pricing engines for outcome tokens.
+//!
+//! Collateral and outcome token base units are valued one to one: a complete
+//! set (one of every outcome) is worth one unit of collateral, so every
+//! marginal price lies in `[0, PRICE_SCALE]` and prices sum to `PRICE_SCALE`.
+//! Settlement pays each winning token one unit, and LP liquidity takes the
+//! pool's profit or loss against traders.
+//! Buy quotes round up and sell quotes round down so that rounding always
+//! favours the pool.
+pub mod cpmm;
+pub mod lmsr;
+mod math;
+
+/// Fixed-point scale of a marginal price, i.e. 1.0 = `PRICE_SCALE`.
+pub const PRICE_SCALE: u64 = 1_000_000_000;
@@ -1,5 +1,15 @@
-pub const GLOBAL_SEED: &'static str = "global_seed";
-pub const MARKET_SEED: &'static str = "market_seed";
-pub const MINT_SEED_A: &'static str = "mint_a_seed";
-pub const MINT_SEED_B: &'static str = "mint_b_seed";
+pub const GLOBAL_SEED: &str = "global_seed";
+pub const MARKET_SEED: &str = "market_seed";
+pub const MINT_SEED_A: &str = "mint_a_seed";
+pub const MINT_SEED_B: &str = "mint_b_seed";
+pub const MINT_SEED_OUTCOME: &str = "mint_outcome_seed";
+pub const LIQUIDITY_SEED: &str = "liquidity_seed";
 pub const SOL_USDC_FEED: &str = "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR";
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Switchboard feed values are i128 scaled by 10^18
+pub const FEED_PRECISION: u32 = 18;
+pub const MAX_OUTCOMES: usize = 8;
+// hard cap on betting and funding fees
+pub const MAX_FEE_BPS: u16 = 1_000;
+// outcome mint decimals must keep 10^decimal within u64
+pub const MAX_DECIMALS: u8 = 9;
@@ -5,8 +5,8 @@ use anchor_lang::prelude::*;
 pub enum ContractError {
     #[msg("Not a valid Switchboard account")]
     InvalidSwitchboardAccount,
-    #[msg("Switchboard feed has not been updated in 5 minutes")]
-    StaleFeed,
+    #[msg("Switchboard feed has no result")]
+    MissingFeedResult,
     #[msg("Switchboard feed exceeded provided confidence interval")]
     ConfidenceIntervalExceeded,
     #[msg("Invalid fund amount")]
@@ -27,4 +27,84 @@ pub enum ContractError {
     MarketNotActive,
     #[msg("Invalid Admin")]
     InvalidAdmin,
+    #[msg("Market is not finished")]
+    MarketNotFinished,
+    #[msg("Invalid token mint")]
+    InvalidMint,
+    #[msg("Token is not the winning outcome")]
+    LosingOutcome,
+    #[msg("Nothing to redeem")]
+    NothingToRedeem,
+    #[msg("Insufficient market balance")]
+    InsufficientMarketBalance,
+    #[msg("Market is already finished")]
+    MarketAlreadyFinished,
+    #[msg("Resolution date has not been reached")]
+    ResolutionDateNotReached,
+    #[msg("Feed does not match the market feed")]
+    InvalidFeed,
+    #[msg("Invalid fee")]
+    InvalidFee,
+    #[msg("Invalid comparison")]
+    InvalidComparison,
+    #[msg("Liquidity cannot be withdrawn while the market is active")]
+    MarketNotWithdrawable,
+    #[msg("Invalid liquidity provider")]
+    InvalidProvider,
+    #[msg("Amount exceeds the market surplus")]
+    InsufficientSurplus,
+    #[msg("Price moved beyond the allowed slippage")]
+    SlippageExceeded,
+    #[msg("Order deadline has passed")]
+    OrderExpired,
+    #[msg("Invalid pricing curve parameters")]
+    InvalidCurve,
+    #[msg("Invalid outcome")]
+    InvalidOutcome,
+    #[msg("Market already has the maximum number of outcomes")]
+    TooManyOutcomes,
+    #[msg("Scalar range must have low below high")]
+    InvalidScalarRange,
+    #[msg("Invalid collateral mint")]
+    InvalidCollateral,
+    #[msg("Collateral token account is required for this market")]
+    MissingCollateralAccount,
+    #[msg("Metadata account is required for legacy token mints")]
+    MissingMetadataAccount,
+    #[msg("Market cannot be cancelled yet")]
+    CancelNotAllowed,
+    #[msg("Market is not cancelled")]
+    MarketNotCancelled,
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    #[msg("Invalid decimal")]
+    InvalidDecimal,
+    #[msg("Oracle grace period must cover the resolution window")]
+    InvalidGracePeriod,
+    #[msg("Signer is not the pending admin")]
+    InvalidPendingAdmin,
+    #[msg("Invalid treasury")]
+    InvalidTreasury,
+    #[msg("Invalid market curator")]
+    InvalidMarketCurator,
+    #[msg("Market has not been approved")]
+    MarketNotApproved,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Market is paused")]
+    MarketPaused,
+    #[msg("Invalid pauser")]
+    InvalidPauser,
+    #[msg("Resolution window must be positive")]
+    InvalidResolutionWindow,
+    #[msg("Feed was not updated within the resolution window")]
+    OutsideResolutionWindow,
+    #[msg("Collateral mint has an unsupported Token-2022 extension")]
+    UnsupportedCollateralExtension,
+    #[msg("Funding deadline must be in the future and before the resolution date")]
+    InvalidFundingDeadline,
+    #[msg("Constant-product markets need the other outcome's pool account")]
+    MissingPoolAccount,
+    #[msg("Market has already been approved")]
+    MarketAlreadyApproved,
 }
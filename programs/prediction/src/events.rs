@@ -1,36 +1,52 @@
-use crate::states::market::MarketStatus;
+use crate::states::{
+    global::GlobalParams,
+    market::{Comparison, Curve, MarketKind, MarketStatus},
+};
 use anchor_lang::prelude::*;
 #[event]
 pub struct GlobalInitialized {
     pub global_id: Pubkey,
+    pub admin: Pubkey,
     pub fee_recipient: Pubkey,
     pub creator_fee_amount: u64,
     pub market_count: u64,
     pub decimal: u8,
-    pub fund_fee_percentage: f64,
-    pub betting_fee_percentage: f64,
+    pub fund_fee_bps: u16,
+    pub betting_fee_bps: u16,
+    pub oracle_grace_period: i64,
+    pub resolution_window: i64,
+    pub crank_reward: u64,
 }
 
 #[event]
 pub struct OracleResUpdated {
-    pub oracle_res: f64,
+    pub oracle_res: i128,
+    pub feed_timestamp: i64,
+    pub payout_bps: Vec<u16>,
+    pub cranker: Pubkey,
+    pub crank_reward: u64,
 }
 
 #[event]
 pub struct MarketCreated {
     pub market_id: Pubkey,
-    pub value: f64,
-    pub range: u8,
+    pub value: i128,
+    pub comparison: Comparison,
+    pub kind: MarketKind,
     pub creator: Pubkey,
     pub feed: Pubkey,
+    pub collateral_mint: Option<Pubkey>,
     pub token_a: Pubkey,
     pub token_b: Pubkey,
     pub market_status: MarketStatus,
+    pub curve: Curve,
+    pub liquidity_param: u64,
     pub token_a_amount: u64,
     pub token_b_amount: u64,
     pub token_price_a: u64,
     pub token_price_b: u64,
-    pub total_reserve: u64,
+    pub funding_target: u64,
+    pub max_confidence_interval: i128,
 }
 
 #[event]
@@ -41,6 +57,120 @@ pub struct MarketStatusUpdated {
 
 #[event]
 pub struct BettingEvent {
-    pub token_a_price: u64,
-    pub token_b_price: u64,
+    pub outcome: u8,
+    pub prices: Vec<u64>,
+}
+
+#[event]
+pub struct RedemptionEvent {
+    pub market_id: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct LiquidityDeposited {
+    pub market_id: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub total_liquidity: u64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+    pub market_id: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub total_liquidity: u64,
+}
+
+#[event]
+pub struct FeesSwept {
+    pub market_id: Pubkey,
+    pub treasury: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub remaining_surplus: u64,
+}
+
+#[event]
+pub struct OutcomeAdded {
+    pub market_id: Pubkey,
+    pub outcome: u8,
+    pub mint: Pubkey,
+    pub reserve: u64,
+}
+
+#[event]
+pub struct CompleteSetEvent {
+    pub market_id: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub is_split: bool,
+}
+
+#[event]
+pub struct MarketCancelled {
+    pub market_id: Pubkey,
+    pub authority: Pubkey,
+    pub payout_reserve: u64,
+    pub total_liquidity: u64,
+}
+
+#[event]
+pub struct Refunded {
+    pub market_id: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_amount: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GlobalUpdated {
+    pub global_id: Pubkey,
+    pub admin: Pubkey,
+    pub old: GlobalParams,
+    pub new: GlobalParams,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub global_id: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub global_id: Pubkey,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct RolesUpdated {
+    pub global_id: Pubkey,
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub pauser: Pubkey,
+    pub market_curator: Pubkey,
+}
+
+#[event]
+pub struct MarketApproved {
+    pub market_id: Pubkey,
+    pub curator: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub global_id: Pubkey,
+    pub market_id: Option<Pubkey>,
+    pub pauser: Pubkey,
+    pub paused: bool,
 }
@@ -0,0 +1,150 @@
+use crate::constants::{GLOBAL_SEED, MARKET_SEED, MINT_SEED_OUTCOME};
+use crate::errors::ContractError;
+use crate::events::OutcomeAdded;
+use crate::states::{global::*, market::*};
+use crate::utils::{create_outcome_mint, OutcomeMintAccounts};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_interface::{mint_to, MintTo, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: OutcomeParams)]
+pub struct AddOutcome<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), &params.market_id.as_bytes()],
+        bump,
+        constraint = market.creator == user.key() @ ContractError::InvalidCreator,
+        constraint = market.market_status == MarketStatus::Prepare @ ContractError::NotPreparing,
+        // the curator approved, and LPs fund, the outcomes as they stand
+        constraint = !market.approved @ ContractError::MarketAlreadyApproved,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    // Metaplex metadata, only for legacy token mints
+    #[account(mut)]
+    ///CHECK: Using seed to validate metadata account
+    metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: created in the handler under `token_program`
+    #[account(
+        mut,
+        seeds = [MINT_SEED_OUTCOME.as_bytes(), market.key().as_ref(), &[market.outcome_count]],
+        bump,
+    )]
+    token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: market ATA of `token_mint`, created by the associated token program
+    #[account(mut)]
+    pub pda_token_account: UncheckedAccount<'info>,
+
+    // legacy token or Token-2022, for the outcome mints
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: token metadata program account
+    pub token_metadata_program: UncheckedAccount<'info>,
+    /// CHECK: rent account
+    pub rent: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AddOutcome<'_> {
+    pub fn add_outcome(ctx: Context<AddOutcome>, params: OutcomeParams) -> Result<()> {
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
+        // scalar markets are always long/short
+        require!(
+            ctx.accounts.market.kind == MarketKind::Discrete,
+            ContractError::InvalidOutcome
+        );
+        // the constant-product curve only prices two outcomes
+        require!(
+            ctx.accounts.market.curve == Curve::Lmsr,
+            ContractError::InvalidCurve
+        );
+
+        // new outcomes start with the same pool inventory as the first one
+        let reserve = ctx.accounts.market.outcomes[0].reserve;
+        let outcome = ctx
+            .accounts
+            .market
+            .add_outcome(ctx.accounts.token_mint.key(), reserve)?;
+        ctx.accounts.market.update_token_price()?;
+        msg!("🎫outcome 🎫 {}", outcome);
+        msg!("🎫prices 🎫 {:?}", ctx.accounts.market.prices());
+
+        let mint_authority_signer = ctx.accounts.market.signer_seeds();
+        let mint_auth_signer_seeds = &[&mint_authority_signer[..]];
+
+        let market_key = ctx.accounts.market.key();
+        let mint_signer: [&[u8]; 4] = [
+            MINT_SEED_OUTCOME.as_bytes(),
+            market_key.as_ref(),
+            std::slice::from_ref(&outcome),
+            std::slice::from_ref(&ctx.bumps.token_mint),
+        ];
+
+        create_outcome_mint(
+            OutcomeMintAccounts {
+                payer: ctx.accounts.user.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                market: ctx.accounts.market.to_account_info(),
+                metadata: ctx.accounts.metadata.as_ref().map(|m| m.to_account_info()),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            params.name.unwrap_or_default(),
+            params.symbol.unwrap_or_default(),
+            params.url.unwrap_or_default(),
+            ctx.accounts.market.decimal,
+            &[&mint_signer[..]],
+            mint_auth_signer_seeds,
+        )?;
+
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.user.to_account_info(),
+                associated_token: ctx.accounts.pda_token_account.to_account_info(),
+                authority: ctx.accounts.market.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        // mint the outcome's pool inventory to market
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    authority: ctx.accounts.market.to_account_info(),
+                    to: ctx.accounts.pda_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+                mint_auth_signer_seeds,
+            ),
+            reserve,
+        )?;
+
+        emit!(OutcomeAdded {
+            market_id: ctx.accounts.market.key(),
+            outcome,
+            mint: ctx.accounts.token_mint.key(),
+            reserve,
+        });
+        Ok(())
+    }
+}
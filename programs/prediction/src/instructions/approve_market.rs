@@ -0,0 +1,40 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::MarketApproved;
+use crate::states::global::Global;
+use crate::states::market::{Market, MarketStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveMarket<'info> {
+    #[account(
+        constraint = curator.key() == global.market_curator @ ContractError::InvalidMarketCurator
+    )]
+    pub curator: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.market_status == MarketStatus::Prepare @ ContractError::NotPreparing,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl ApproveMarket<'_> {
+    pub fn approve_market(ctx: Context<ApproveMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.approved = true;
+        msg!("🎫approved market 🎫 {}", market.market_id);
+
+        emit!(MarketApproved {
+            market_id: market.key(),
+            curator: ctx.accounts.curator.key(),
+        });
+        Ok(())
+    }
+}
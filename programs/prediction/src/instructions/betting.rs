@@ -1,9 +1,10 @@
 use crate::constants::GLOBAL_SEED;
 use crate::errors::ContractError;
 use crate::states::{global::*, market::*};
-use anchor_lang::{prelude::*, solana_program};
-use anchor_spl::token::{Mint, TokenAccount, Token};
-use crate::utils::token_transfer;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::quote::quote_buy;
+use crate::utils::{collateral_in, cpmm_pool, pool_complete_sets, token_leg, token_transfer};
 use crate::events::BettingEvent;
 
 #[derive(Accounts)]
@@ -20,20 +21,34 @@ pub struct Betting<'info> {
 
     #[account(mut)]
     /// CHECK reward token
-    token_mint: Box<Account<'info, Mint>>,
+    token_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = market
+        associated_token::authority = market,
+        associated_token::token_program = token_program
     )]
-    pub pda_token_account: Box<Account<'info, TokenAccount>>,
+    pub pda_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // the other outcome's mint and pool account, only for constant-product
+    // markets, whose pool holds the complete sets trades split and merge
+    #[account(mut)]
+    pub other_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = other_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program
+    )]
+    pub pda_other_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut, 
         associated_token::mint = token_mint,
-        associated_token::authority = user.key()
+        associated_token::authority = user.key(),
+        associated_token::token_program = token_program
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: global fee authority is checked in constraint
     #[account(
@@ -53,7 +68,35 @@ pub struct Betting<'info> {
         bump
     )]
     pub global: Account<'info, Global>,
-    pub token_program: Program<'info, Token>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = fee_authority,
+        token::token_program = collateral_token_program
+    )]
+    pub fee_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     /// CHECK: associated token program account
     pub associated_token_program: UncheckedAccount<'info>,
     /// CHECK: token metadata program account
@@ -62,93 +105,143 @@ pub struct Betting<'info> {
     pub system_program: Program<'info, System>,
 }
 
-impl Betting<'_> {
-    pub fn betting(ctx: Context<Betting>, params: BettingParams) -> Result<()> {
+impl<'info> Betting<'info> {
+    pub fn betting(
+        ctx: Context<'_, '_, '_, 'info, Betting<'info>>,
+        params: BettingParams,
+    ) -> Result<()> {
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
         let market = &mut ctx.accounts.market;
 
-        let decimal_multiplier = 10u64.pow(ctx.accounts.global.decimal as u32);
-        
-        // Transfer sol to market
-        let token_price = if params.is_yes {
-            market.token_price_a
-        } else {
-            market.token_price_b
-        };
+        require!(
+            Clock::get()?.unix_timestamp <= params.deadline,
+            ContractError::OrderExpired
+        );
 
-        let sol_to_buy = params.amount.checked_mul(decimal_multiplier).ok_or(ContractError::ArithmeticError)?.checked_div(10u64.pow(9)).ok_or(ContractError::ArithmeticError)?.checked_mul(token_price).ok_or(ContractError::ArithmeticError)?;
+        require_keys_eq!(
+            ctx.accounts.token_mint.key(),
+            market.outcome_mint(params.outcome)?,
+            ContractError::InvalidMint
+        );
+
+        let quote = quote_buy(
+            market,
+            params.amount,
+            params.outcome,
+            ctx.accounts.global.betting_fee_bps,
+        )?;
+        let token_amount = quote.token_amount;
+        let sol_to_buy = quote.collateral;
+        let crank_fee = market.accrue_crank_fee(quote.fee, ctx.accounts.global.crank_reward);
+        let fee_amount_to_auth = quote.fee - crank_fee;
         msg!("🎫sol_to_buy 🎫 {}", sol_to_buy);
-        let transfer_market_instruction = solana_program::system_instruction::transfer(
-            ctx.accounts.user.key,
-            market.to_account_info().key,
-            sol_to_buy,
+        require!(
+            quote.total <= params.max_cost_lamports,
+            ContractError::SlippageExceeded
         );
 
-        anchor_lang::solana_program::program::invoke_signed(
-            &transfer_market_instruction,
-        &[
-                ctx.accounts.user.to_account_info(),
-                market.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[],
+        // Transfer collateral to market
+        collateral_in(
+            ctx.accounts.user.to_account_info(),
+            market.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            ctx.accounts.system_program.to_account_info(),
+            sol_to_buy,
         )?;
             
         let mint_authority_signer: [&[u8]; 3] =
-            Market::get_signer(&market.bump, &params.market_id.as_bytes());
+            Market::get_signer(&market.bump, params.market_id.as_bytes());
         let mint_auth_signer_seeds = &[&mint_authority_signer[..]];
 
-        let token_amount = params
-            .amount
-            .checked_mul(decimal_multiplier)
-            .ok_or(ContractError::ArithmeticError)?;
+        // The collateral becomes complete sets in the pool before tokens
+        // leave it, as the constant-product curve prices it
+        if let Some(pool) = cpmm_pool(
+            market,
+            params.outcome,
+            (
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.pda_token_account.to_account_info(),
+            ),
+            ctx.accounts.other_token_mint.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.pda_other_token_account.as_ref().map(|a| a.to_account_info()),
+        )? {
+            pool_complete_sets(
+                pool,
+                market.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                mint_auth_signer_seeds,
+                sol_to_buy,
+                true,
+            )?;
+        }
+
         msg!("🎫token_amount to user 🎫 {}", token_amount);
 
         token_transfer(
             ctx.accounts.pda_token_account.to_account_info(),
             ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.token_mint,
             market.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
             mint_auth_signer_seeds,
             token_amount,
         )?;
-            
-        // Transfer fee to fee authority
-        
-        let fee_amount_to_auth = sol_to_buy
-            .checked_mul(ctx.accounts.global.betting_fee_percentage as u64)
-            .ok_or(ContractError::ArithmeticError)?
-            .checked_div(100)
-            .ok_or(ContractError::ArithmeticError)?;
 
-        msg!("🎫fee_amount_to_auth 🎫 {}", fee_amount_to_auth);
-    
-        let transfer_auth_instruction = solana_program::system_instruction::transfer(
-            ctx.accounts.user.key,
-            ctx.accounts.fee_authority.key,
-            fee_amount_to_auth,
-        );
-
-        anchor_lang::solana_program::program::invoke_signed(
-            &transfer_auth_instruction,
-            &[
+        // Hold back the crank reward's share of the fee in the market
+        if crank_fee > 0 {
+            msg!("🎫crank_fee 🎫 {}", crank_fee);
+            collateral_in(
                 ctx.accounts.user.to_account_info(),
-                ctx.accounts.fee_authority.to_account_info(),
+                market.to_account_info(),
+                token_leg(
+                    market.collateral_mint,
+                    ctx.accounts.collateral_mint.as_deref(),
+                    ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.collateral_token_program.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
                 ctx.accounts.system_program.to_account_info(),
-            ],
-            &[],
+                crank_fee,
+            )?;
+        }
+
+        // Transfer fee to fee authority
+        msg!("🎫fee_amount_to_auth 🎫 {}", fee_amount_to_auth);
+        collateral_in(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.fee_authority.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.fee_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            ctx.accounts.system_program.to_account_info(),
+            fee_amount_to_auth,
         )?;
 
-        if params.is_yes {
-            market.yes_amount += 1;
-        } else {
-            market.no_amount += 1;
-        }
+        market.payout_reserve = market
+            .payout_reserve
+            .checked_add(sol_to_buy)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        market.outcomes[params.outcome as usize].bets += 1;
 
-        let _ = market.set_token_price(params.amount, params.is_yes)?;
+        market.apply_trade(token_amount, sol_to_buy, params.outcome, true)?;
 
-        emit!(BettingEvent{
-            token_a_price: market.token_price_a,
-            token_b_price: market.token_price_b
+        emit!(BettingEvent {
+            outcome: params.outcome,
+            prices: market.prices(),
         });
         Ok(())
     }
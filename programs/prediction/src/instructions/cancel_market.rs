@@ -0,0 +1,53 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::{MarketCancelled, MarketStatusUpdated};
+use crate::states::global::Global;
+use crate::states::market::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelMarket<'info> {
+    #[account(
+        constraint = authority.key() == market.creator || authority.key() == global.admin @ ContractError::InvalidAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl CancelMarket<'_> {
+    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            market.can_cancel(
+                Clock::get()?.unix_timestamp,
+                ctx.accounts.global.oracle_grace_period
+            ),
+            ContractError::CancelNotAllowed
+        );
+
+        // LPs withdraw their deposits and token holders refund their tokens
+        market.cancel()?;
+        msg!("🎫cancelled market_status 🎫 {:?}", market.market_status);
+
+        emit!(MarketCancelled {
+            market_id: market.key(),
+            authority: ctx.accounts.authority.key(),
+            payout_reserve: market.payout_reserve,
+            total_liquidity: market.total_liquidity,
+        });
+        emit!(MarketStatusUpdated {
+            market_id: market.key(),
+            market_status: market.market_status,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::CompleteSetEvent;
+use crate::states::{
+    global::Global,
+    market::{Market, MarketStatus},
+};
+use crate::utils::{collateral_in, collateral_out, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface};
+
+/// Splits collateral into one token of every outcome, or merges a full set
+/// back into collateral. Remaining accounts are, for each outcome in order,
+/// its mint followed by the user's token account for it, then any accounts
+/// the collateral mint's transfer hook needs.
+#[derive(Accounts)]
+pub struct CompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.market_status == MarketStatus::Active @ ContractError::MarketNotActive,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CompleteSet<'info> {
+    pub fn split<'c: 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CompleteSet<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidFundAmount);
+        // merging stays open while paused so sets can always be unwound
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
+        let (outcome_accounts, hook_accounts) =
+            ctx.accounts.outcome_accounts(ctx.remaining_accounts)?;
+
+        // Transfer collateral to market
+        collateral_in(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.market.to_account_info(),
+            token_leg(
+                ctx.accounts.market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                hook_accounts,
+            )?,
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+        )?;
+
+        let signer = ctx.accounts.market.signer_seeds();
+        for (mint, user_token_account) in outcome_accounts {
+            mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        authority: ctx.accounts.market.to_account_info(),
+                        to: user_token_account,
+                        mint,
+                    },
+                    &[&signer[..]],
+                ),
+                amount,
+            )?;
+        }
+
+        ctx.accounts.market.split(amount)?;
+        msg!("🎫split amount 🎫 {}", amount);
+
+        emit!(CompleteSetEvent {
+            market_id: ctx.accounts.market.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            is_split: true,
+        });
+        Ok(())
+    }
+
+    pub fn merge<'c: 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CompleteSet<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidFundAmount);
+        let (outcome_accounts, hook_accounts) =
+            ctx.accounts.outcome_accounts(ctx.remaining_accounts)?;
+
+        for (mint, user_token_account) in outcome_accounts {
+            burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint,
+                        from: user_token_account,
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        ctx.accounts.market.merge(amount)?;
+        msg!("🎫merge amount 🎫 {}", amount);
+
+        let signer = ctx.accounts.market.signer_seeds();
+        collateral_out(
+            ctx.accounts.market.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            token_leg(
+                ctx.accounts.market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                hook_accounts,
+            )?,
+            &[&signer[..]],
+            amount,
+        )?;
+
+        emit!(CompleteSetEvent {
+            market_id: ctx.accounts.market.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            is_split: false,
+        });
+        Ok(())
+    }
+
+    /// Pairs each outcome mint with the user's token account for it, checking
+    /// the mints against the market in outcome order, and returns the
+    /// transfer hook accounts that follow them.
+    #[allow(clippy::type_complexity)]
+    fn outcome_accounts<'a>(
+        &self,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(
+        Vec<(AccountInfo<'info>, AccountInfo<'info>)>,
+        &'a [AccountInfo<'info>],
+    )> {
+        let outcomes = self.market.active_outcomes();
+        require!(
+            remaining_accounts.len() >= outcomes.len() * 2,
+            ContractError::InvalidOutcome
+        );
+        let (outcome_accounts, hook_accounts) = remaining_accounts.split_at(outcomes.len() * 2);
+
+        let pairs = outcomes
+            .iter()
+            .zip(outcome_accounts.chunks(2))
+            .map(|(outcome, accounts)| {
+                require_keys_eq!(accounts[0].key(), outcome.mint, ContractError::InvalidMint);
+                Ok((accounts[0].clone(), accounts[1].clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((pairs, hook_accounts))
+    }
+}
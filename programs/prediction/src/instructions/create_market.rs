@@ -1,18 +1,28 @@
-use crate::constants::{GLOBAL_SEED, MARKET_SEED, MINT_SEED_A, MINT_SEED_B};
+use crate::constants::{GLOBAL_SEED, MARKET_SEED, MAX_DECIMALS, MINT_SEED_A, MINT_SEED_B};
 use crate::errors::ContractError;
 use crate::events::MarketCreated;
 use crate::states::{global::*, market::*};
+use crate::utils::{check_collateral_mint, create_outcome_mint, OutcomeMintAccounts};
 use anchor_lang::{prelude::*, solana_program};
-use anchor_spl::{
-    metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
-    },
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use switchboard_on_demand::{
+    on_demand::accounts::pull_feed::PullFeedAccountData, ON_DEMAND_DEVNET_PID,
+    ON_DEMAND_MAINNET_PID,
 };
 
 #[derive(Accounts)]
 #[instruction(params: MarketParams)]
 pub struct CreateMarket<'info> {
     #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: global fee authority is checked in constraint
+    #[account(
+        mut,
+        constraint = fee_authority.key() == global_pda.fee_authority @ ContractError::InvalidFeeAuthority
+    )]
+    pub fee_authority: AccountInfo<'info>,
+
     #[account(
         init,
         payer = user,
@@ -20,47 +30,58 @@ pub struct CreateMarket<'info> {
         seeds = [MARKET_SEED.as_bytes(), &params.market_id.as_bytes()],
         bump
     )]
-    pub user: Signer<'info>,
-
-    /// CHECK: global fee authority is checked in constraint
-
-    pub fee_authority: AccountInfo<'info>,
-
-    /// CHECK: global fee authority is checked in constraint
     pub market: Box<Account<'info, Market>>,
-    pub global_pda: Box<Account<'info, Global>>,
-    /// CHECK: via switchboard sdk
-    pub feed: AccountInfo<'info>,
 
-    ///CHECK: Using seed to validate metadata account
     #[account(
         seeds = [GLOBAL_SEED.as_bytes()],
         bump
     )]
-    #[account(mut)]
-    metadata_a: UncheckedAccount<'info>,
+    pub global_pda: Box<Account<'info, Global>>,
+
+    /// CHECK: owner is checked in constraint, data via switchboard sdk
+    #[account(
+        constraint = feed.owner == &ON_DEMAND_DEVNET_PID || feed.owner == &ON_DEMAND_MAINNET_PID @ ContractError::InvalidSwitchboardAccount
+    )]
+    pub feed: AccountInfo<'info>,
 
+    // Metaplex metadata, only for legacy token mints
+    #[account(mut)]
+    ///CHECK: Using seed to validate metadata account
+    metadata_a: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
     ///CHECK: Using seed to validate metadata account
+    metadata_b: Option<UncheckedAccount<'info>>,
 
+    /// CHECK: created in the handler under `token_program`
     #[account(
-        init,
+        mut,
         seeds = [MINT_SEED_A.as_bytes(), market.key().as_ref()],
         bump,
-        payer = user,
-        mint::decimals = global_pda.decimal,
-        mint::authority = market,
     )]
-    token_mint_a: Box<Account<'info, Mint>>,
+    token_mint_a: UncheckedAccount<'info>,
+    /// CHECK: created in the handler under `token_program`
     #[account(
-        init,
+        mut,
         seeds = [MINT_SEED_B.as_bytes(), market.key().as_ref()],
         bump,
+    )]
+    token_mint_b: UncheckedAccount<'info>,
+
+    // optional SPL collateral; the market holds native SOL without it
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        init,
         payer = user,
-        mint::decimals = global_pda.decimal,
-        mint::authority = market
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
     )]
-    
-    pub token_program: Program<'info, Token>,
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    // legacy token or Token-2022, for the outcome mints
+    pub token_program: Interface<'info, TokenInterface>,
     /// CHECK: associated token program account
     pub associated_token_program: UncheckedAccount<'info>,
     /// CHECK: token metadata program account
@@ -68,43 +89,90 @@ pub struct CreateMarket<'info> {
     /// CHECK: rent account
     pub rent: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
-    token_mint_b: Box<Account<'info, Mint>>,
 }
 
 impl CreateMarket<'_> {
     pub fn create_market(ctx: Context<CreateMarket>, params: MarketParams) -> Result<()> {
+        ctx.accounts.global_pda.assert_live(&ctx.accounts.market)?;
+        // feed must be a switchboard pull feed
+        PullFeedAccountData::parse(ctx.accounts.feed.data.borrow())
+            .map_err(|_| ContractError::InvalidSwitchboardAccount)?;
+        require!(
+            params.comparison.is_valid(),
+            ContractError::InvalidComparison
+        );
+        require!(params.kind.is_valid(), ContractError::InvalidScalarRange);
+        require!(
+            ctx.accounts.collateral_mint.is_some() == ctx.accounts.collateral_vault.is_some(),
+            ContractError::MissingCollateralAccount
+        );
+        if let Some(mint) = &ctx.accounts.collateral_mint {
+            check_collateral_mint(&mint.to_account_info())?;
+        }
+        ctx.accounts.market.market_id = params.market_id.clone();
+        ctx.accounts.market.collateral_mint =
+            ctx.accounts.collateral_mint.as_ref().map(|mint| mint.key());
+        // the global threshold is in lamports, so token markets set their own
+        ctx.accounts.market.funding_target = match ctx.accounts.market.collateral_mint {
+            None if params.funding_target == 0 => ctx.accounts.global_pda.market_count,
+            _ => params.funding_target,
+        };
+        require!(
+            ctx.accounts.market.funding_target > 0,
+            ContractError::InvalidFundAmount
+        );
+        require!(
+            params.curve != Curve::Lmsr || params.liquidity_param > 0,
+            ContractError::InvalidCurve
+        );
+        // funding has to close in the future and no later than resolution
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            params.funding_deadline > now && params.funding_deadline <= params.date,
+            ContractError::InvalidFundingDeadline
+        );
+
+        // outcome tokens settle one collateral base unit per base unit, so
+        // token markets mint them with the collateral's decimals
+        let decimal = match &ctx.accounts.collateral_mint {
+            Some(mint) => mint.decimals,
+            None => ctx.accounts.global_pda.decimal,
+        };
+        require!(decimal <= MAX_DECIMALS, ContractError::InvalidDecimal);
+
         // update market settings
-        let _ = ctx.accounts.market.update_market_settings(
-            params.value,
+        ctx.accounts.market.update_market_settings(
+            &params,
+            ctx.accounts.user.key(),
             ctx.accounts.feed.key(),
             ctx.accounts.token_mint_a.key(),
             ctx.accounts.token_mint_b.key(),
-            params.token_amount,
-            params.token_price,
-            params.date,
-        );
+            decimal,
+        )?;
 
         let mint_authority_signer: [&[u8]; 3] =
-            Market::get_signer(&ctx.bumps.market, &params.market_id.as_bytes());
+            Market::get_signer(&ctx.bumps.market, params.market_id.as_bytes());
         let mint_auth_signer_seeds = &[&mint_authority_signer[..]];
         ctx.accounts.market.bump = ctx.bumps.market;
-        msg!("🎫here metadata creation 🎫");
-        // initialize Yes token metadata
-        ctx.accounts.intialize_meta(
+        msg!("🎫here mint creation 🎫");
+        // initialize Yes token mint and metadata
+        ctx.accounts.initialize_mint(
             params.name_a,
             true,
             params.symbol_a,
             params.url_a,
+            ctx.bumps.token_mint_a,
             mint_auth_signer_seeds,
         )?;
-        msg!("🎫here metadata creation 2 🎫");
+        msg!("🎫here mint creation 2 🎫");
 
-        // initialize No token metadata
-        ctx.accounts.intialize_meta(
+        // initialize No token mint and metadata
+        ctx.accounts.initialize_mint(
             params.name_b,
             false,
             params.symbol_b,
             params.url_b,
+            ctx.bumps.token_mint_b,
             mint_auth_signer_seeds,
         )?;
 
@@ -129,81 +197,66 @@ impl CreateMarket<'_> {
         emit!(MarketCreated {
             market_id: ctx.accounts.market.key(),
             value: ctx.accounts.market.value,
-            range: ctx.accounts.market.range,
+            comparison: ctx.accounts.market.comparison,
+            kind: ctx.accounts.market.kind,
             creator: ctx.accounts.user.key(),
             feed: ctx.accounts.feed.key(),
+            collateral_mint: ctx.accounts.market.collateral_mint,
             token_a: ctx.accounts.token_mint_a.key(),
             token_b: ctx.accounts.token_mint_b.key(),
             market_status: ctx.accounts.market.market_status,
-            token_a_amount: ctx.accounts.market.token_a_amount,
-            token_b_amount: ctx.accounts.market.token_b_amount,
-            token_price_a: ctx.accounts.market.token_price_a,
-            token_price_b: ctx.accounts.market.token_price_b,
-            total_reserve: ctx.accounts.market.total_reserve,
+            curve: ctx.accounts.market.curve,
+            liquidity_param: ctx.accounts.market.liquidity_param,
+            token_a_amount: ctx.accounts.market.outcomes[0].reserve,
+            token_b_amount: ctx.accounts.market.outcomes[1].reserve,
+            token_price_a: ctx.accounts.market.outcomes[0].price,
+            token_price_b: ctx.accounts.market.outcomes[1].price,
+            funding_target: ctx.accounts.market.funding_target,
+            max_confidence_interval: ctx.accounts.market.max_confidence_interval,
         });
 
         Ok(())
     }
-    pub fn intialize_meta(
-        &mut self,
+    pub fn initialize_mint(
+        &self,
         name: Option<String>,
         is_token_a: bool,
         symbol: Option<String>,
         uri: Option<String>,
+        mint_bump: u8,
         mint_auth_signer_seeds: &[&[&[u8]]; 1],
     ) -> Result<()> {
-        let mint_info: AccountInfo<'_>;
-        if is_token_a {
-            mint_info = self.token_mint_a.to_account_info();
+        let (mint, metadata, mint_seed) = if is_token_a {
+            (&self.token_mint_a, &self.metadata_a, MINT_SEED_A)
         } else {
-            mint_info = self.token_mint_b.to_account_info();
-        }
-
-        let mint_authority_info = self.market.to_account_info();
-        let metadata_info = if is_token_a {
-            self.metadata_a.to_account_info()
-        } else {
-            self.metadata_b.to_account_info()
+            (&self.token_mint_b, &self.metadata_b, MINT_SEED_B)
         };
+        let market_key = self.market.key();
+        let mint_signer: [&[u8]; 3] = [
+            mint_seed.as_bytes(),
+            market_key.as_ref(),
+            std::slice::from_ref(&mint_bump),
+        ];
 
-        let mut para_name: String = "".to_string();
-        let mut para_symbol: String = "".to_string();
-        let mut para_uri: String = "".to_string();
-
-        if let Some(name) = name {
-            para_name = name.to_string();
-        }
-        if let Some(symbol) = symbol {
-            para_symbol = symbol.to_string();
-        }
-        if let Some(uri) = uri {
-            para_uri = uri.to_string();
-        }
-
-        let token_data: DataV2 = DataV2 {
-            name: para_name.clone(),
-            symbol: para_symbol.clone(),
-            uri: para_uri.clone(),
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
-        let metadata_ctx = CpiContext::new_with_signer(
-            self.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
+        create_outcome_mint(
+            OutcomeMintAccounts {
                 payer: self.user.to_account_info(),
-                mint: mint_info.clone(),
-                metadata: metadata_info.clone(),
-                update_authority: mint_authority_info.clone(),
-                mint_authority: mint_authority_info.clone(),
+                mint: mint.to_account_info(),
+                market: self.market.to_account_info(),
+                metadata: metadata.as_ref().map(|m| m.to_account_info()),
+                token_program: self.token_program.to_account_info(),
+                token_metadata_program: self.token_metadata_program.to_account_info(),
                 system_program: self.system_program.to_account_info(),
                 rent: self.rent.to_account_info(),
             },
+            name.unwrap_or_default(),
+            symbol.unwrap_or_default(),
+            uri.unwrap_or_default(),
+            self.market.decimal,
+            &[&mint_signer[..]],
             mint_auth_signer_seeds,
-        );
-        create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
-        msg!("CollectionToken::intialize_meta: done");
+        )?;
+        msg!("CollectionToken::initialize_mint: done");
         Ok(())
     }
 }
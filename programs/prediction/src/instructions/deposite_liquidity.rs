@@ -1,11 +1,14 @@
-use crate::constants::GLOBAL_SEED;
+use crate::constants::{GLOBAL_SEED, LIQUIDITY_SEED};
 use crate::errors::ContractError;
-use crate::events::MarketStatusUpdated;
+use crate::events::{LiquidityDeposited, MarketStatusUpdated};
 use crate::states::{
     global::Global,
+    liquidity_position::LiquidityPosition,
     market::{Market, MarketStatus},
 };
-use anchor_lang::{prelude::*, solana_program};
+use crate::utils::{calc_fee, collateral_in, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct DepositLiquidity<'info> {
@@ -22,71 +25,125 @@ pub struct DepositLiquidity<'info> {
     #[account(
         mut,
         constraint = market.market_status == MarketStatus::Prepare @ ContractError::NotPreparing,
+        constraint = market.approved @ ContractError::MarketNotApproved,
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + LiquidityPosition::INIT_SPACE,
+        seeds = [LIQUIDITY_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub liquidity_position: Account<'info, LiquidityPosition>,
+
     #[account(
         seeds = [GLOBAL_SEED.as_bytes()],
         bump
     )]
     pub global: Account<'info, Global>,
 
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = fee_authority,
+        token::token_program = collateral_token_program
+    )]
+    pub fee_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn deposit_liquidity(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
-
+pub fn deposit_liquidity<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositLiquidity<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.global.assert_live(&ctx.accounts.market)?;
     require!(amount >= 100000, ContractError::InvalidFundAmount);
-    // Transfer sol to market
-    let liquidity_transfer_instruction = solana_program::system_instruction::transfer(
-        ctx.accounts.user.key,
-        &ctx.accounts.market.key(),
-        amount,
-    );
+    let collateral_mint = ctx.accounts.market.collateral_mint;
+    let user_collateral = ctx
+        .accounts
+        .user_collateral_account
+        .as_ref()
+        .map(|a| a.to_account_info());
 
-    anchor_lang::solana_program::program::invoke_signed(
-        &liquidity_transfer_instruction,
-        &[
-            ctx.accounts.user.to_account_info(),
-            ctx.accounts.market.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-        &[],
+    // Transfer collateral to market
+    collateral_in(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.market.to_account_info(),
+        token_leg(
+            collateral_mint,
+            ctx.accounts.collateral_mint.as_deref(),
+            user_collateral.clone(),
+            ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            ctx.remaining_accounts,
+        )?,
+        ctx.accounts.system_program.to_account_info(),
+        amount,
     )?;
 
-    let fee_amount_to_auth = amount
-        .checked_mul(ctx.accounts.global.fund_fee_percentage as u64)
-        .ok_or(ContractError::ArithmeticError)?
-        .checked_div(100)
-        .ok_or(ContractError::ArithmeticError)?;
+    let fee_amount_to_auth = calc_fee(amount, ctx.accounts.global.fund_fee_bps)?;
 
-    // Transfer sol to fee authority
-    let transfer_instruction = solana_program::system_instruction::transfer(
-        ctx.accounts.user.key,
-        &ctx.accounts.fee_authority.key(),
+    // Transfer fee to fee authority
+    collateral_in(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.fee_authority.to_account_info(),
+        token_leg(
+            collateral_mint,
+            ctx.accounts.collateral_mint.as_deref(),
+            user_collateral,
+            ctx.accounts.fee_collateral_account.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            ctx.remaining_accounts,
+        )?,
+        ctx.accounts.system_program.to_account_info(),
         fee_amount_to_auth,
-    );
-
-    anchor_lang::solana_program::program::invoke_signed(
-        &transfer_instruction,
-        &[
-            ctx.accounts.user.to_account_info(),
-            ctx.accounts.fee_authority.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-        &[],
     )?;
 
+    // Record the provider's stake
+    let shares = ctx.accounts.market.add_liquidity(amount)?;
+    let position = &mut ctx.accounts.liquidity_position;
+    position.market = ctx.accounts.market.key();
+    position.provider = ctx.accounts.user.key();
+    position.bump = ctx.bumps.liquidity_position;
+    position.add(amount, shares)?;
+
+    emit!(LiquidityDeposited {
+        market_id: ctx.accounts.market.key(),
+        provider: ctx.accounts.user.key(),
+        amount,
+        shares,
+        total_liquidity: ctx.accounts.market.total_liquidity,
+    });
+
     // Update market status
-    let market_balance = ctx.accounts.market.get_lamports();
-    if market_balance >= ctx.accounts.global.market_count {
-        ctx.accounts.market.market_status = MarketStatus::Active;
+    let market = &mut ctx.accounts.market;
+    if market.total_liquidity >= market.funding_target {
+        market.market_status = MarketStatus::Active;
     }
-    msg!("🎫deposit liquidity market_balance 🎫 {}", market_balance);
-    msg!(
-        "🎫deposit liquidity global.market_count 🎫 {}",
-        ctx.accounts.global.market_count
-    );
+    msg!("🎫deposit liquidity total_liquidity 🎫 {}", market.total_liquidity);
+    msg!("🎫deposit liquidity funding_target 🎫 {}", market.funding_target);
     msg!(
         "🎫deposit liquidity market_status 🎫 {:?}",
         ctx.accounts.market.market_status
@@ -1,20 +1,25 @@
 use crate::constants::GLOBAL_SEED;
 use crate::errors::ContractError;
-use crate::events::OracleResUpdated;
+use crate::events::{MarketStatusUpdated, OracleResUpdated};
 use crate::states::global::Global;
-use crate::states::market::Market;
+use crate::states::market::{Market, MarketStatus};
+use crate::utils::{collateral_out, to_feed_precision, token_leg};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use switchboard_on_demand::on_demand::accounts::pull_feed::PullFeedAccountData;
 
+/// Resolves a market from its feed once the resolution date has passed.
+/// Anyone can crank it; the feed binding, resolution window and confidence
+/// checks are what make the result trustworthy, not the caller.
 #[derive(Accounts)]
 pub struct GetOracleRes<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
     #[account(
         mut,
-        constraint = user.key() == global.admin @ ContractError::InvalidAdmin
+        constraint = market.market_status != MarketStatus::Finished @ ContractError::MarketAlreadyFinished,
+        constraint = market.market_status == MarketStatus::Active @ ContractError::MarketNotActive,
     )]
-    pub user: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: global fee authority is checked in constraint
     pub market: Box<Account<'info, Market>>,
 
     #[account(
@@ -23,37 +28,108 @@ pub struct GetOracleRes<'info> {
     )]
     pub global: Box<Account<'info, Global>>,
 
-    /// CHECK: via switchboard sdk
+    /// CHECK: bound to the market feed in constraint, data via switchboard sdk
+    #[account(
+        address = market.feed @ ContractError::InvalidFeed
+    )]
     pub feed: AccountInfo<'info>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // receives the crank reward
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn get_oracle_res(ctx: Context<GetOracleRes>) -> Result<()> {
+pub fn get_oracle_res<'info>(ctx: Context<'_, '_, '_, 'info, GetOracleRes<'info>>) -> Result<()> {
+    // Resolution stays open while paused: the window only lasts so long, and
+    // a pause must not push the market into cancellation
     let market = &mut ctx.accounts.market;
 
+    require!(
+        Clock::get()?.unix_timestamp >= market.resolution_date,
+        ContractError::ResolutionDateNotReached
+    );
+
     let feed_account = ctx.accounts.feed.data.borrow();
-    let feed: std::cell::Ref<'_, PullFeedAccountData> =
-        PullFeedAccountData::parse(feed_account).unwrap();
+    let feed: std::cell::Ref<'_, PullFeedAccountData> = PullFeedAccountData::parse(feed_account)
+        .map_err(|_| ContractError::InvalidSwitchboardAccount)?;
 
     msg!("🎫price 🎫 {:?}", feed.value());
-    msg!("🎫range 🎫 {:?}", market.range);
+    msg!("🎫std_dev 🎫 {:?}", feed.std_dev());
+    msg!("🎫comparison 🎫 {:?}", market.comparison);
+
+    // Settle on the first prices after the resolution date, not whenever the
+    // crank runs; a market whose feed missed the window falls back to
+    // cancellation. The feed keeps no timestamps for its earlier results, so
+    // a short window is what limits which update a cranker can settle on
+    let feed_timestamp = feed.last_update_timestamp;
+    msg!("🎫feed_timestamp 🎫 {}", feed_timestamp);
+    require!(
+        market.in_resolution_window(feed_timestamp, ctx.accounts.global.resolution_window),
+        ContractError::OutsideResolutionWindow
+    );
 
-    let feed_value: f64 = feed.value().unwrap().try_into().unwrap();
+    let feed_value = to_feed_precision(feed.value().ok_or(ContractError::MissingFeedResult)?)?;
+    let feed_std_dev = to_feed_precision(feed.std_dev().ok_or(ContractError::MissingFeedResult)?)?;
+    require!(
+        feed_std_dev <= market.max_confidence_interval,
+        ContractError::ConfidenceIntervalExceeded
+    );
 
-    market.result = if market.range == 0 && market.value > feed_value {
-        true
-    } else if market.range == 1 && market.value == feed_value {
-        true
-    } else if market.range == 2 && market.value < feed_value {
-        true
-    } else {
-        false
-    };
+    market.resolve(feed_value, feed.result.slot, feed_timestamp)?;
 
-    msg!("🎫result 🎫 {:?}", ctx.accounts.market.result);
+    msg!("🎫winning_outcome 🎫 {:?}", market.winning_outcome);
+
+    // Pay the crank reward out of the betting fees the market held back,
+    // never out of bettor or LP funds
+    let crank_reward = market.take_crank_pool();
+    if crank_reward > 0 {
+        msg!("🎫crank_reward 🎫 {}", crank_reward);
+        let signer = market.signer_seeds();
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            &[&signer[..]],
+            crank_reward,
+        )?;
+    }
 
     emit!(OracleResUpdated {
-        oracle_res: feed.value().unwrap().try_into().unwrap(),
+        oracle_res: feed_value,
+        feed_timestamp,
+        payout_bps: market.active_outcomes().iter().map(|o| o.payout_bps).collect(),
+        cranker: ctx.accounts.user.key(),
+        crank_reward,
+    });
+    emit!(MarketStatusUpdated {
+        market_id: market.key(),
+        market_status: market.market_status,
     });
     Ok(())
 }
@@ -18,24 +18,30 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn init(ctx: Context<Initialize>, params: GlobalParams) -> Result<()> {
+pub fn init(ctx: Context<Initialize>, params: GlobalParams, admin: Option<Pubkey>) -> Result<()> {
     let global = &mut ctx.accounts.global;
-    global.admin = ctx.accounts.payer.key();
-    global.fee_authority = params.fee_authority;
-    global.creator_fee_amount = params.creator_fee_amount;
-    global.market_count = params.market_count;
-    global.decimal = params.decimal;
-    global.fund_fee_percentage = params.fund_fee_percentage;
-    global.betting_fee_percentage = params.betting_fee_percentage;
+    // the payer only funds the account; admin can be a multisig vault
+    global.admin = admin.unwrap_or(ctx.accounts.payer.key());
+    global.pending_admin = None;
+    // every role starts with the admin until update_roles hands it off
+    let admin = global.admin;
+    global.treasury = admin;
+    global.pauser = admin;
+    global.market_curator = admin;
+    global.update_settings(params)?;
 
     emit!(GlobalInitialized {
         global_id: global.key(),
+        admin: global.admin,
         fee_recipient: global.fee_authority,
         creator_fee_amount: global.creator_fee_amount,
         market_count: global.market_count,
         decimal: global.decimal,
-        fund_fee_percentage: global.fund_fee_percentage,
-        betting_fee_percentage: global.betting_fee_percentage,
+        fund_fee_bps: global.fund_fee_bps,
+        betting_fee_bps: global.betting_fee_bps,
+        oracle_grace_period: global.oracle_grace_period,
+        resolution_window: global.resolution_window,
+        crank_reward: global.crank_reward,
     });
 
     Ok(())
@@ -1,7 +1,19 @@
+pub mod add_outcome;
+pub mod approve_market;
 pub mod betting;
+pub mod cancel_market;
+pub mod complete_set;
 pub mod create_market;
 pub mod deposite_liquidity;
 pub mod get_oracle_res;
 pub mod init;
+pub mod pause;
+pub mod redeem;
+pub mod refund;
+pub mod sell;
+pub mod sweep_fees;
 pub mod token_mint;
-pub mod withdraw;
+pub mod transfer_admin;
+pub mod update_global;
+pub mod update_roles;
+pub mod withdraw_liquidity;
@@ -0,0 +1,51 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::PauseUpdated;
+use crate::states::global::Global;
+use crate::states::market::Market;
+use anchor_lang::prelude::*;
+
+/// Pauses or unpauses a single market when one is passed, otherwise the
+/// whole program. Redeem, refund, merge and liquidity withdrawal ignore both
+/// flags so users can always exit.
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        constraint = pauser.key() == global.pauser @ ContractError::InvalidPauser
+    )]
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(mut)]
+    pub market: Option<Box<Account<'info, Market>>>,
+}
+
+impl SetPause<'_> {
+    pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
+        let market_id = match ctx.accounts.market.as_mut() {
+            Some(market) => {
+                market.paused = paused;
+                Some(market.key())
+            }
+            None => {
+                ctx.accounts.global.paused = paused;
+                None
+            }
+        };
+        msg!("🎫paused 🎫 {} {:?}", paused, market_id);
+
+        emit!(PauseUpdated {
+            global_id: ctx.accounts.global.key(),
+            market_id,
+            pauser: ctx.accounts.pauser.key(),
+            paused,
+        });
+        Ok(())
+    }
+}
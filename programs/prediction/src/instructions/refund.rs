@@ -0,0 +1,114 @@
+use crate::errors::ContractError;
+use crate::events::Refunded;
+use crate::states::market::{Market, MarketStatus};
+use crate::utils::{collateral_out, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+/// Refunds outcome tokens of a cancelled market for their share of the
+/// payout reserve. Refunds follow the tokens, not the wallet that bought
+/// them, so tokens can only be refunded once.
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.market_status == MarketStatus::Cancelled @ ContractError::MarketNotCancelled,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        constraint = market.outcome_index(&token_mint.key()).is_some() @ ContractError::InvalidMint
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = user.key(),
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Refund<'info> {
+    pub fn refund(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        let outcome = market
+            .outcome_index(&ctx.accounts.token_mint.key())
+            .ok_or(ContractError::InvalidMint)?;
+
+        // Burning the whole balance is what prevents a second refund
+        let token_amount = ctx.accounts.user_token_account.amount;
+        require!(token_amount > 0, ContractError::NothingToRefund);
+
+        let amount = market.redeem_share(outcome, token_amount)?;
+        require!(amount > 0, ContractError::NothingToRefund);
+        msg!("🎫refund token_amount 🎫 {}", token_amount);
+        msg!("🎫refund amount 🎫 {}", amount);
+
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        let signer = market.signer_seeds();
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            &[&signer[..]],
+            amount,
+        )?;
+
+        emit!(Refunded {
+            market_id: market.key(),
+            user: ctx.accounts.user.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            token_amount,
+            amount,
+        });
+        Ok(())
+    }
+}
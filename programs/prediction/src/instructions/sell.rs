@@ -0,0 +1,213 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::BettingEvent;
+use crate::states::{global::*, market::*};
+use crate::quote::quote_sell;
+use crate::utils::{collateral_out, cpmm_pool, pool_complete_sets, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct Sell<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    token_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program
+    )]
+    pub pda_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // the other outcome's mint and pool account, only for constant-product
+    // markets, whose pool holds the complete sets trades split and merge
+    #[account(mut)]
+    pub other_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = other_token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program
+    )]
+    pub pda_other_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = user.key(),
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: global fee authority is checked in constraint
+    #[account(
+        mut,
+        constraint = fee_authority.key() == global.fee_authority @ ContractError::InvalidFeeAuthority
+    )]
+    pub fee_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = market.market_status == MarketStatus::Active @ ContractError::MarketNotActive,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = fee_authority,
+        token::token_program = collateral_token_program
+    )]
+    pub fee_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Sell<'info> {
+    pub fn sell(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, params: SellParams) -> Result<()> {
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            Clock::get()?.unix_timestamp <= params.deadline,
+            ContractError::OrderExpired
+        );
+
+        require_keys_eq!(
+            ctx.accounts.token_mint.key(),
+            market.outcome_mint(params.outcome)?,
+            ContractError::InvalidMint
+        );
+
+        let quote = quote_sell(
+            market,
+            params.amount,
+            params.outcome,
+            ctx.accounts.global.betting_fee_bps,
+        )?;
+        let token_amount = quote.token_amount;
+        let sol_to_return = quote.collateral;
+        let fee_amount_to_auth = quote.fee;
+        let sol_to_user = quote.total;
+        msg!("🎫sol_to_return 🎫 {}", sol_to_return);
+        msg!("🎫fee_amount_to_auth 🎫 {}", fee_amount_to_auth);
+        require!(
+            sol_to_user >= params.min_proceeds,
+            ContractError::SlippageExceeded
+        );
+
+        // Sells are paid from bettor funds only, never from LP liquidity
+        market.payout_reserve = market
+            .payout_reserve
+            .checked_sub(sol_to_return)
+            .ok_or(ContractError::InsufficientMarketBalance)?;
+
+        msg!("🎫token_amount to market 🎫 {}", token_amount);
+
+        // Return tokens to market
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.pda_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let signer = market.signer_seeds();
+        let signer_seeds = &[&signer[..]];
+
+        // Merge the proceeds back out of the pool as complete sets
+        if let Some(pool) = cpmm_pool(
+            market,
+            params.outcome,
+            (
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.pda_token_account.to_account_info(),
+            ),
+            ctx.accounts.other_token_mint.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.pda_other_token_account.as_ref().map(|a| a.to_account_info()),
+        )? {
+            pool_complete_sets(
+                pool,
+                market.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                signer_seeds,
+                sol_to_return,
+                false,
+            )?;
+        }
+
+        // Pay out collateral net of fee, and the fee to fee authority
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            signer_seeds,
+            sol_to_user,
+        )?;
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.fee_authority.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.fee_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            signer_seeds,
+            fee_amount_to_auth,
+        )?;
+
+        market.apply_trade(token_amount, sol_to_return, params.outcome, false)?;
+
+        emit!(BettingEvent {
+            outcome: params.outcome,
+            prices: market.prices(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::FeesSwept;
+use crate::states::global::Global;
+use crate::states::market::Market;
+use crate::utils::{collateral_balance, collateral_out, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        constraint = treasury.key() == global.treasury @ ContractError::InvalidTreasury
+    )]
+    pub treasury: Signer<'info>,
+
+    /// CHECK: global fee authority is checked in constraint
+    #[account(
+        mut,
+        constraint = fee_authority.key() == global.fee_authority @ ContractError::InvalidFeeAuthority
+    )]
+    pub fee_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = fee_authority,
+        token::token_program = collateral_token_program
+    )]
+    pub fee_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SweepFees<'info> {
+    pub fn sweep_fees(
+        ctx: Context<'_, '_, '_, 'info, SweepFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
+        let market = &ctx.accounts.market;
+
+        let (balance, rent_exempt) = collateral_balance(
+            &market.to_account_info(),
+            market.collateral_mint,
+            ctx.accounts.collateral_vault.as_deref(),
+        )?;
+        let surplus = market.surplus(balance, rent_exempt)?;
+        msg!("market balance {}", balance);
+        msg!("market surplus {}", surplus);
+        require!(amount <= surplus, ContractError::InsufficientSurplus);
+
+        let signer = market.signer_seeds();
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.fee_authority.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.fee_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            &[&signer[..]],
+            amount,
+        )?;
+
+        emit!(FeesSwept {
+            market_id: market.key(),
+            treasury: ctx.accounts.treasury.key(),
+            recipient: ctx.accounts.fee_authority.key(),
+            amount,
+            remaining_surplus: surplus - amount,
+        });
+        Ok(())
+    }
+}
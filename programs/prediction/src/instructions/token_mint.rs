@@ -3,7 +3,7 @@ use crate::errors::ContractError;
 use crate::states::global::Global;
 use crate::states::market::{Market, MarketStatus};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction(market_id: String)]
@@ -12,16 +12,18 @@ pub struct TokenMint<'info> {
         init_if_needed,
         payer = user,
         associated_token::mint = token_mint_a,
-        associated_token::authority = market
+        associated_token::authority = market,
+        associated_token::token_program = token_program
     )]
-    pub pda_token_a_account: Box<Account<'info, TokenAccount>>,
+    pub pda_token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = token_mint_b,
-        associated_token::authority = market
+        associated_token::authority = market,
+        associated_token::token_program = token_program
     )]
-    pub pda_token_b_account: Box<Account<'info, TokenAccount>>,
+    pub pda_token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -36,7 +38,8 @@ pub struct TokenMint<'info> {
     #[account(
         mut,
         seeds = [MARKET_SEED.as_bytes(), &market_id.as_bytes()],
-        bump
+        bump,
+        constraint = market.market_status == MarketStatus::Prepare @ ContractError::NotPreparing,
     )]
     /// CHECK: global fee authority is checked in constraint
     pub market: Box<Account<'info, Market>>,
@@ -56,12 +59,12 @@ pub struct TokenMint<'info> {
 
     /// CHECK: Yes token mint
     #[account(mut)]
-    token_mint_a: Box<Account<'info, Mint>>,
+    token_mint_a: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK: No token mint
     #[account(mut)]
-    token_mint_b: Box<Account<'info, Mint>>,
+    token_mint_b: Box<InterfaceAccount<'info, Mint>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     /// CHECK: associated token program account
     pub associated_token_program: UncheckedAccount<'info>,
     /// CHECK: token metadata program account
@@ -73,24 +76,14 @@ pub struct TokenMint<'info> {
 
 impl TokenMint<'_> {
     pub fn token_mint(ctx: Context<TokenMint>, market_id: String) -> Result<()> {
+        ctx.accounts.global.assert_live(&ctx.accounts.market)?;
         let mint_authority_signer: [&[u8]; 3] =
-            Market::get_signer(&ctx.bumps.market, &market_id.as_bytes());
+            Market::get_signer(&ctx.bumps.market, market_id.as_bytes());
         let mint_auth_signer_seeds = &[&mint_authority_signer[..]];
 
-        let decimal_multiplier = 10u64.pow(ctx.accounts.global.decimal as u32);
-        let token_a_amount = ctx
-            .accounts
-            .market
-            .token_a_amount
-            .checked_mul(decimal_multiplier)
-            .ok_or(ContractError::ArithmeticError)?;
-
-        let token_b_amount = ctx
-            .accounts
-            .market
-            .token_b_amount
-            .checked_mul(decimal_multiplier)
-            .ok_or(ContractError::ArithmeticError)?;
+        // reserves are already stored in token base units
+        let token_a_amount = ctx.accounts.market.outcomes[0].reserve;
+        let token_b_amount = ctx.accounts.market.outcomes[1].reserve;
         msg!("🎫token_a_amount 🎫{}", token_a_amount);
         // mint "Yes" token to market
         mint_to(
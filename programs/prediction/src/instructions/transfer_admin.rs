@@ -0,0 +1,70 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::{AdminProposed, AdminTransferred};
+use crate::states::global::Global;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        constraint = admin.key() == global.admin @ ContractError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl ProposeAdmin<'_> {
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+
+        // proposing again replaces any earlier pending admin
+        global.pending_admin = Some(new_admin);
+        msg!("🎫pending_admin 🎫 {}", new_admin);
+
+        emit!(AdminProposed {
+            global_id: global.key(),
+            admin: global.admin,
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        constraint = global.pending_admin == Some(pending_admin.key()) @ ContractError::InvalidPendingAdmin
+    )]
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl AcceptAdmin<'_> {
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+
+        let old_admin = global.admin;
+        global.admin = ctx.accounts.pending_admin.key();
+        global.pending_admin = None;
+        msg!("🎫new admin 🎫 {}", global.admin);
+
+        emit!(AdminTransferred {
+            global_id: global.key(),
+            old_admin,
+            new_admin: global.admin,
+        });
+        Ok(())
+    }
+}
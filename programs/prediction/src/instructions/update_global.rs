@@ -0,0 +1,40 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::GlobalUpdated;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateGlobal<'info> {
+    #[account(
+        constraint = admin.key() == global.admin @ ContractError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl UpdateGlobal<'_> {
+    pub fn update_global(ctx: Context<UpdateGlobal>, params: UpdateGlobalParams) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+
+        // existing markets keep the decimal they were created with
+        let old = global.params();
+        let new = old.merge(params);
+        global.update_settings(new.clone())?;
+        msg!("🎫global updated 🎫 {:?}", new);
+
+        emit!(GlobalUpdated {
+            global_id: global.key(),
+            admin: ctx.accounts.admin.key(),
+            old,
+            new,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::constants::GLOBAL_SEED;
+use crate::errors::ContractError;
+use crate::events::RolesUpdated;
+use crate::states::global::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    #[account(
+        constraint = admin.key() == global.admin @ ContractError::InvalidAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_SEED.as_bytes()],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+}
+
+impl UpdateRoles<'_> {
+    pub fn update_roles(ctx: Context<UpdateRoles>, roles: RolesParams) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+        global.set_roles(roles);
+
+        emit!(RolesUpdated {
+            global_id: global.key(),
+            admin: global.admin,
+            treasury: global.treasury,
+            pauser: global.pauser,
+            market_curator: global.market_curator,
+        });
+        Ok(())
+    }
+}
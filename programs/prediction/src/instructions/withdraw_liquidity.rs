@@ -0,0 +1,108 @@
+use crate::constants::LIQUIDITY_SEED;
+use crate::errors::ContractError;
+use crate::events::LiquidityWithdrawn;
+use crate::states::{
+    liquidity_position::LiquidityPosition,
+    market::{Market, MarketStatus},
+};
+use crate::utils::{collateral_out, token_leg};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.market_status != MarketStatus::Active @ ContractError::MarketNotWithdrawable,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [LIQUIDITY_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        bump = liquidity_position.bump,
+        constraint = liquidity_position.provider == user.key() @ ContractError::InvalidProvider,
+    )]
+    pub liquidity_position: Account<'info, LiquidityPosition>,
+
+    // collateral accounts, only for markets with a collateral mint
+    #[account(address = market.collateral_mint.unwrap_or_default() @ ContractError::InvalidCollateral)]
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = market,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = user,
+        token::token_program = collateral_token_program
+    )]
+    pub user_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // token program of the collateral mint, unused by SOL markets
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawLiquidity<'info> {
+    pub fn withdraw_liquidity(
+        ctx: Context<'_, '_, '_, 'info, WithdrawLiquidity<'info>>,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let position = &ctx.accounts.liquidity_position;
+
+        // A market that never went live or was cancelled refunds the deposit
+        // as is, a finished one pays the share of liquidity left after winner
+        // payouts
+        let amount = if matches!(
+            market.market_status,
+            MarketStatus::Prepare | MarketStatus::Cancelled
+        ) {
+            market.total_liquidity = market
+                .total_liquidity
+                .checked_sub(position.amount)
+                .ok_or(ContractError::ArithmeticError)?;
+            market.total_lp_shares = market
+                .total_lp_shares
+                .checked_sub(position.shares)
+                .ok_or(ContractError::ArithmeticError)?;
+            position.amount
+        } else {
+            market.remove_liquidity(position.shares)?
+        };
+        msg!("🎫withdraw liquidity amount 🎫 {}", amount);
+
+        let signer = market.signer_seeds();
+        collateral_out(
+            market.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            token_leg(
+                market.collateral_mint,
+                ctx.accounts.collateral_mint.as_deref(),
+                ctx.accounts.collateral_vault.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.user_collateral_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            &[&signer[..]],
+            amount,
+        )?;
+
+        emit!(LiquidityWithdrawn {
+            market_id: market.key(),
+            provider: ctx.accounts.user.key(),
+            amount,
+            shares: position.shares,
+            total_liquidity: market.total_liquidity,
+        });
+        Ok(())
+    }
+}
@@ -1,18 +1,22 @@
 use anchor_lang::prelude::*;
+pub mod amm;
 pub mod constants;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod quote;
 pub mod states;
 pub mod utils;
 
 use instructions::{
-    betting::*, create_market::*, deposite_liquidity::*, get_oracle_res::*, init::*, token_mint::*,
-    withdraw::*,
+    add_outcome::*, approve_market::*, betting::*, cancel_market::*, complete_set::*,
+    create_market::*, deposite_liquidity::*, get_oracle_res::*, init::*, pause::*, redeem::*,
+    refund::*, sell::*, sweep_fees::*, token_mint::*, transfer_admin::*, update_global::*,
+    update_roles::*, withdraw_liquidity::*,
 };
 use states::{
-    global::GlobalParams,
-    market::{BettingParams, MarketParams},
+    global::{GlobalParams, RolesParams, UpdateGlobalParams},
+    market::{BettingParams, MarketParams, OutcomeParams, SellParams},
 };
 
 declare_id!("Bki3CWk4AmVF78zvh81rup2EK2iJY4WRCUXesAv8TECF");
@@ -21,11 +25,39 @@ declare_id!("Bki3CWk4AmVF78zvh81rup2EK2iJY4WRCUXesAv8TECF");
 pub mod prediction {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, params: GlobalParams) -> Result<()> {
-        init(ctx, params)
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        params: GlobalParams,
+        admin: Option<Pubkey>,
+    ) -> Result<()> {
+        init(ctx, params, admin)
     }
 
-    pub fn get_res(ctx: Context<GetOracleRes>) -> Result<()> {
+    pub fn update_global(ctx: Context<UpdateGlobal>, params: UpdateGlobalParams) -> Result<()> {
+        UpdateGlobal::update_global(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ProposeAdmin::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        AcceptAdmin::accept_admin(ctx)
+    }
+
+    pub fn update_roles(ctx: Context<UpdateRoles>, roles: RolesParams) -> Result<()> {
+        UpdateRoles::update_roles(ctx, roles)
+    }
+
+    pub fn pause(ctx: Context<SetPause>) -> Result<()> {
+        SetPause::set_pause(ctx, true)
+    }
+
+    pub fn unpause(ctx: Context<SetPause>) -> Result<()> {
+        SetPause::set_pause(ctx, false)
+    }
+
+    pub fn get_res<'info>(ctx: Context<'_, '_, '_, 'info, GetOracleRes<'info>>) -> Result<()> {
         get_oracle_res(ctx)
     }
 
@@ -33,19 +65,75 @@ pub mod prediction {
         CreateMarket::create_market(ctx, params)
     }
 
-    pub fn add_liquidity(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
+    pub fn approve_market(ctx: Context<ApproveMarket>) -> Result<()> {
+        ApproveMarket::approve_market(ctx)
+    }
+
+    pub fn add_outcome(ctx: Context<AddOutcome>, params: OutcomeParams) -> Result<()> {
+        AddOutcome::add_outcome(ctx, params)
+    }
+
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositLiquidity<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         deposit_liquidity(ctx, amount)
     }
 
-    pub fn create_bet(ctx: Context<Betting>, params: BettingParams) -> Result<()> {
+    pub fn withdraw_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawLiquidity<'info>>,
+    ) -> Result<()> {
+        WithdrawLiquidity::withdraw_liquidity(ctx)
+    }
+
+    pub fn create_bet<'info>(
+        ctx: Context<'_, '_, '_, 'info, Betting<'info>>,
+        params: BettingParams,
+    ) -> Result<()> {
         Betting::betting(ctx, params)
     }
 
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        params: SellParams,
+    ) -> Result<()> {
+        Sell::sell(ctx, params)
+    }
+
+    pub fn split<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CompleteSet<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        CompleteSet::split(ctx, amount)
+    }
+
+    pub fn merge<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CompleteSet<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        CompleteSet::merge(ctx, amount)
+    }
+
     pub fn mint_token(ctx: Context<TokenMint>, market_id: String) -> Result<()> {
         TokenMint::token_mint(ctx, market_id)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        Withdraw::withdraw(ctx, amount)
+    pub fn redeem<'info>(ctx: Context<'_, '_, '_, 'info, Redeem<'info>>) -> Result<()> {
+        Redeem::redeem(ctx)
+    }
+
+    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+        CancelMarket::cancel_market(ctx)
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        Refund::refund(ctx)
+    }
+
+    pub fn sweep_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        SweepFees::sweep_fees(ctx, amount)
     }
 }
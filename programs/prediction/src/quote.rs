@@ -0,0 +1,84 @@
+//! Trade quotes computed from a `Market` snapshot.
+//!
+//! The program prices every buy and sell through these functions, so clients
+//! that deserialize a market account and call them get exactly the amounts
+//! the instruction will charge or pay. Nothing here touches accounts, sysvars
+//! or logs, which keeps it usable from the `cpi`/`no-entrypoint` build.
+use crate::errors::ContractError;
+use crate::states::market::Market;
+use crate::utils::calc_fee;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Quote {
+    // traded outcome tokens, in base units
+    pub token_amount: u64,
+    // lamports moved by the curve, before fees
+    pub collateral: u64,
+    pub fee: u64,
+    // lamports paid by a buyer or received by a seller, fee included
+    pub total: u64,
+    // lamports per whole token of each outcome after the trade
+    pub prices: Vec<u64>,
+}
+
+/// Quotes buying `amount` whole tokens of `outcome`.
+pub fn quote_buy(
+    market: &Market,
+    amount: u64,
+    outcome: u8,
+    fee_bps: u16,
+) -> Result<Quote> {
+    let token_amount = to_base_units(amount, market.decimal)?;
+    let collateral = market.quote(token_amount, outcome, true)?;
+    let fee = calc_fee(collateral, fee_bps)?;
+    let total = collateral
+        .checked_add(fee)
+        .ok_or(ContractError::ArithmeticError)?;
+
+    after_trade(market, token_amount, collateral, fee, total, outcome, true)
+}
+
+/// Quotes selling `amount` whole tokens of `outcome` back to the pool.
+pub fn quote_sell(
+    market: &Market,
+    amount: u64,
+    outcome: u8,
+    fee_bps: u16,
+) -> Result<Quote> {
+    let token_amount = to_base_units(amount, market.decimal)?;
+    let collateral = market.quote(token_amount, outcome, false)?;
+    let fee = calc_fee(collateral, fee_bps)?;
+    let total = collateral
+        .checked_sub(fee)
+        .ok_or(ContractError::ArithmeticError)?;
+
+    after_trade(market, token_amount, collateral, fee, total, outcome, false)
+}
+
+fn to_base_units(amount: u64, decimal: u8) -> Result<u64> {
+    Ok(amount
+        .checked_mul(10u64.pow(decimal as u32))
+        .ok_or(ContractError::ArithmeticError)?)
+}
+
+fn after_trade(
+    market: &Market,
+    token_amount: u64,
+    collateral: u64,
+    fee: u64,
+    total: u64,
+    outcome: u8,
+    is_buy: bool,
+) -> Result<Quote> {
+    let mut market = market.clone();
+    market.apply_trade(token_amount, collateral, outcome, is_buy)?;
+
+    Ok(Quote {
+        token_amount,
+        collateral,
+        fee,
+        total,
+        prices: market.prices(),
+    })
+}
@@ -1,23 +1,179 @@
+use crate::constants::{MAX_DECIMALS, MAX_FEE_BPS};
+use crate::errors::ContractError;
+use crate::states::market::Market;
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Global {
+    // may be a multisig vault PDA; admin instructions only need its signature
     pub admin: Pubkey,
+    // set by propose_admin, becomes admin once it signs accept_admin
+    pub pending_admin: Option<Pubkey>,
+    // sweeps market surplus to the fee authority
+    pub treasury: Pubkey,
+    // pauses and unpauses trading
+    pub pauser: Pubkey,
+    // approves new markets before they can be funded
+    pub market_curator: Pubkey,
+    // halts trading, funding and market creation; exits stay open
+    pub paused: bool,
     pub fee_authority: Pubkey,
     pub creator_fee_amount: u64,
     pub decimal: u8,
     pub market_count: u64,
-    pub betting_fee_percentage: f64,
-    pub fund_fee_percentage: f64,
+    pub betting_fee_bps: u16,
+    pub fund_fee_bps: u16,
+    // seconds after the resolution date before an unresolved market can be
+    // cancelled as oracle-dead
+    pub oracle_grace_period: i64,
+    // seconds after a market's resolution date in which the feed
+    // must have been updated for the market to resolve on it
+    pub resolution_window: i64,
+    // held back from each market's betting fees, up to this much in the
+    // market's collateral, and paid to whoever resolves the market
+    pub crank_reward: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
+impl Global {
+    pub fn set_roles(&mut self, roles: RolesParams) {
+        self.treasury = roles.treasury.unwrap_or(self.treasury);
+        self.pauser = roles.pauser.unwrap_or(self.pauser);
+        self.market_curator = roles.market_curator.unwrap_or(self.market_curator);
+    }
+
+    /// Fails if the program or `market` is paused.
+    pub fn assert_live(&self, market: &Market) -> Result<()> {
+        require!(!self.paused, ContractError::ProgramPaused);
+        require!(!market.paused, ContractError::MarketPaused);
+        Ok(())
+    }
+
+    pub fn params(&self) -> GlobalParams {
+        GlobalParams {
+            fee_authority: self.fee_authority,
+            creator_fee_amount: self.creator_fee_amount,
+            market_count: self.market_count,
+            decimal: self.decimal,
+            betting_fee_bps: self.betting_fee_bps,
+            fund_fee_bps: self.fund_fee_bps,
+            oracle_grace_period: self.oracle_grace_period,
+            resolution_window: self.resolution_window,
+            crank_reward: self.crank_reward,
+        }
+    }
+
+    pub fn update_settings(&mut self, params: GlobalParams) -> Result<()> {
+        params.validate()?;
+        self.fee_authority = params.fee_authority;
+        self.creator_fee_amount = params.creator_fee_amount;
+        self.market_count = params.market_count;
+        self.decimal = params.decimal;
+        self.betting_fee_bps = params.betting_fee_bps;
+        self.fund_fee_bps = params.fund_fee_bps;
+        self.oracle_grace_period = params.oracle_grace_period;
+        self.resolution_window = params.resolution_window;
+        self.crank_reward = params.crank_reward;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct GlobalParams {
     pub fee_authority: Pubkey,
     pub creator_fee_amount: u64,
     pub market_count: u64,
     pub decimal: u8,
-    pub betting_fee_percentage: f64,
-    pub fund_fee_percentage: f64,
+    pub betting_fee_bps: u16,
+    pub fund_fee_bps: u16,
+    pub oracle_grace_period: i64,
+    pub resolution_window: i64,
+    pub crank_reward: u64,
+}
+
+impl GlobalParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.betting_fee_bps <= MAX_FEE_BPS && self.fund_fee_bps <= MAX_FEE_BPS,
+            ContractError::InvalidFee
+        );
+        require!(self.decimal <= MAX_DECIMALS, ContractError::InvalidDecimal);
+        require!(
+            self.resolution_window > 0,
+            ContractError::InvalidResolutionWindow
+        );
+        // an active market must stay resolvable for the whole window before
+        // it can be cancelled as oracle-dead
+        require!(
+            self.oracle_grace_period >= self.resolution_window,
+            ContractError::InvalidGracePeriod
+        );
+        Ok(())
+    }
+
+    /// Returns these params with every field set in `update` replaced.
+    pub fn merge(&self, update: UpdateGlobalParams) -> GlobalParams {
+        GlobalParams {
+            fee_authority: update.fee_authority.unwrap_or(self.fee_authority),
+            creator_fee_amount: update.creator_fee_amount.unwrap_or(self.creator_fee_amount),
+            market_count: update.market_count.unwrap_or(self.market_count),
+            decimal: update.decimal.unwrap_or(self.decimal),
+            betting_fee_bps: update.betting_fee_bps.unwrap_or(self.betting_fee_bps),
+            fund_fee_bps: update.fund_fee_bps.unwrap_or(self.fund_fee_bps),
+            oracle_grace_period: update
+                .oracle_grace_period
+                .unwrap_or(self.oracle_grace_period),
+            resolution_window: update.resolution_window.unwrap_or(self.resolution_window),
+            crank_reward: update.crank_reward.unwrap_or(self.crank_reward),
+        }
+    }
+}
+
+// fields left as None keep their current value
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateGlobalParams {
+    pub fee_authority: Option<Pubkey>,
+    pub creator_fee_amount: Option<u64>,
+    pub market_count: Option<u64>,
+    pub decimal: Option<u8>,
+    pub betting_fee_bps: Option<u16>,
+    pub fund_fee_bps: Option<u16>,
+    pub oracle_grace_period: Option<i64>,
+    pub resolution_window: Option<i64>,
+    pub crank_reward: Option<u64>,
+}
+
+// roles left as None keep their current key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RolesParams {
+    pub treasury: Option<Pubkey>,
+    pub pauser: Option<Pubkey>,
+    pub market_curator: Option<Pubkey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(oracle_grace_period: i64, resolution_window: i64) -> GlobalParams {
+        GlobalParams {
+            fee_authority: Pubkey::default(),
+            creator_fee_amount: 0,
+            market_count: 1,
+            decimal: 9,
+            betting_fee_bps: 100,
+            fund_fee_bps: 100,
+            oracle_grace_period,
+            resolution_window,
+            crank_reward: 0,
+        }
+    }
+
+    #[test]
+    fn grace_period_must_cover_the_resolution_window() {
+        assert!(params(600, 600).validate().is_ok());
+        // otherwise a creator could cancel while the market can still resolve
+        assert!(params(300, 600).validate().is_err());
+        assert!(params(600, 0).validate().is_err());
+    }
 }
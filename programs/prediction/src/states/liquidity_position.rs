@@ -0,0 +1,27 @@
+use crate::errors::ContractError;
+use anchor_lang::prelude::*;
+
+/// A provider's stake in a market, one per (market, provider) pair.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct LiquidityPosition {
+    pub market: Pubkey,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+impl LiquidityPosition {
+    pub fn add(&mut self, amount: u64, shares: u64) -> Result<()> {
+        self.amount = self
+            .amount
+            .checked_add(amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.shares = self
+            .shares
+            .checked_add(shares)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(())
+    }
+}
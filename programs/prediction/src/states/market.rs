@@ -1,59 +1,188 @@
-use crate::constants::MARKET_SEED;
+use crate::amm::{cpmm, lmsr, PRICE_SCALE};
+use crate::constants::{BPS_DENOMINATOR, FEED_PRECISION, MARKET_SEED, MAX_OUTCOMES};
 use crate::errors::ContractError;
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace, Debug)]
-
 pub struct Market {
+    #[max_len(32)]
+    pub market_id: String,
     pub creator: Pubkey,
     pub feed: Pubkey,
+    // SPL collateral held in the market's vault ATA, native SOL when unset
+    pub collateral_mint: Option<Pubkey>,
+    // feed values are scaled by 10^FEED_PRECISION
+    pub value: i128,
+    pub comparison: Comparison,
+    pub kind: MarketKind,
     pub market_status: MarketStatus,
-    pub result: bool,
-    pub token_a: Pubkey,
-    pub token_b: Pubkey,
-    pub token_a_amount: u64,
-    pub token_b_amount: u64,
-    pub token_price_a: u64,
-    pub token_price_b: u64,
-    pub yes_amount: u16,
-    pub no_amount: u16,
-    pub total_reserve: u64,
+    // set by the market curator, required before liquidity can be added
+    pub approved: bool,
+    // halts trading on this market only; exits stay open
+    pub paused: bool,
+    pub winning_outcome: u8,
+    // outcome mint decimals, the collateral's for token markets
+    pub decimal: u8,
+    pub curve: Curve,
+    // LMSR liquidity parameter `b`, in token base units
+    pub liquidity_param: u64,
+    pub outcome_count: u8,
+    // only the first `outcome_count` entries are in use
+    pub outcomes: [Outcome; MAX_OUTCOMES],
+    // lamports owed to outcome token holders, kept apart from LP liquidity
+    pub payout_reserve: u64,
+    pub total_liquidity: u64,
+    pub total_lp_shares: u64,
+    // betting fees held back to pay whoever resolves the market
+    pub crank_pool: u64,
+    // LP liquidity, in collateral base units, that activates the market
+    pub funding_target: u64,
     pub resolution_date: i64,
+    // a market still preparing after this can be cancelled
+    pub funding_deadline: i64,
+    pub max_confidence_interval: i128,
+    pub resolved_value: i128,
+    pub resolved_slot: u64,
+    // feed update time of the value the market resolved on
+    pub resolved_timestamp: i64,
     pub bump: u8,
 }
 
 impl Market {
-    pub fn set_token_price(&mut self, sell_token_amount: u64, is_yes: bool) -> Result<()> {
-        if is_yes {
-            pub range: u8,
+    /// Collateral exchanged for `token_amount` base units of an outcome at
+    /// the market's curve.
+    pub fn quote(&self, token_amount: u64, outcome: u8, is_buy: bool) -> Result<u64> {
+        let index = self.outcome_index_checked(outcome)?;
+        let collateral = match (self.curve, is_buy) {
+            (Curve::ConstantProduct, true) => {
+                let (reserve_in, reserve_out) = self.binary_reserves(index);
+                cpmm::buy_cost(reserve_in, reserve_out, token_amount)
+            }
+            (Curve::ConstantProduct, false) => {
+                let (reserve_in, reserve_out) = self.binary_reserves(index);
+                cpmm::sell_proceeds(reserve_in, reserve_out, token_amount)
+            }
+            (Curve::Lmsr, true) => {
+                lmsr::buy_cost(&self.quantities(), index, token_amount, self.liquidity_param)
+            }
+            (Curve::Lmsr, false) => {
+                lmsr::sell_proceeds(&self.quantities(), index, token_amount, self.liquidity_param)
+            }
+        };
+        Ok(collateral.ok_or(ContractError::ArithmeticError)?)
+    }
+
+    /// Moves the curve by a trade quoted with `quote` and refreshes prices.
+    pub fn apply_trade(
+        &mut self,
+        token_amount: u64,
+        collateral: u64,
+        outcome: u8,
+        is_buy: bool,
+    ) -> Result<()> {
+        let index = self.outcome_index_checked(outcome)?;
+        match self.curve {
+            // the caller mints or burns the complete sets in the pool
+            Curve::ConstantProduct => {
+                let (reserve_in, reserve_out) = self.binary_reserves(index);
+                let (reserve_in, reserve_out) = cpmm::reserves_after(
+                    reserve_in,
+                    reserve_out,
+                    token_amount,
+                    collateral,
+                    is_buy,
+                )
+                .ok_or(ContractError::ArithmeticError)?;
+                self.outcomes[index].reserve = reserve_in;
+                self.outcomes[1 - index].reserve = reserve_out;
+            }
+            // LMSR reserves are plain inventory
+            Curve::Lmsr => {
+                let reserve = &mut self.outcomes[index].reserve;
+                *reserve = if is_buy {
+                    reserve.checked_sub(token_amount)
+                } else {
+                    reserve.checked_add(token_amount)
+                }
+                .ok_or(ContractError::ArithmeticError)?;
+            }
+        }
 
-            self.token_price_b = self.token_price_b + sell_token_amount;
+        let sold = &mut self.outcomes[index].sold;
+        *sold = if is_buy {
+            sold.checked_add(token_amount)
         } else {
-            self.token_a_amount = self.token_a_amount - sell_token_amount;
-            self.token_b_amount = self.token_b_amount - sell_token_amount;
-            self.token_price_a = self.token_price_a + sell_token_amount;
+            sold.checked_sub(token_amount)
         }
+        .ok_or(ContractError::ArithmeticError)?;
 
-        self.token_price_a = self
-            .total_reserve
-            .checked_div(self.token_a_amount)
-            .ok_or(ContractError::ArithmeticError)?
-            .checked_mul(self.token_a_amount + self.token_b_amount)
-            .ok_or(ContractError::ArithmeticError)?;
-        self.token_price_b = self
-            .total_reserve
-            .checked_mul(self.token_a_amount + self.token_b_amount)
-            .ok_or(ContractError::ArithmeticError)?
+        self.update_token_price()
+    }
+
+    /// Sets the lamport price of one whole token of each outcome from the
+    /// curve's marginal prices.
+    pub fn update_token_price(&mut self) -> Result<()> {
+        let quantities = self.quantities();
+        let decimal_multiplier = 10u128.pow(self.decimal as u32);
+
+        for index in 0..self.outcome_count as usize {
+            let price = match self.curve {
+                Curve::ConstantProduct => {
+                    let (reserve_in, reserve_out) = self.binary_reserves(index);
+                    cpmm::price(reserve_in, reserve_out)
+                }
+                Curve::Lmsr => lmsr::price(&quantities, index, self.liquidity_param),
+            }
             .ok_or(ContractError::ArithmeticError)?;
-            .checked_div(self.token_b_amount)
-            msg!("🤖token_price_b 🤖 {}", self.token_price_b);
-            msg!("🤖token_a_amount 🤖 {}", self.token_a_amount);
-            msg!("🤖token_b_amount 🤖 {}", self.token_b_amount);
-            msg!("🤖token_price_a 🤖 {}", self.token_price_a);
+
+            self.outcomes[index].price =
+                (price as u128 * decimal_multiplier / PRICE_SCALE as u128) as u64;
+        }
         Ok(())
     }
 
+    /// Current price of every outcome, in outcome order.
+    pub fn prices(&self) -> Vec<u64> {
+        self.active_outcomes().iter().map(|o| o.price).collect()
+    }
+
+    pub fn active_outcomes(&self) -> &[Outcome] {
+        &self.outcomes[..self.outcome_count as usize]
+    }
+
+    /// Index of the outcome minted by `mint`, if any.
+    pub fn outcome_index(&self, mint: &Pubkey) -> Option<u8> {
+        self.active_outcomes()
+            .iter()
+            .position(|o| &o.mint == mint)
+            .map(|index| index as u8)
+    }
+
+    pub fn outcome_mint(&self, outcome: u8) -> Result<Pubkey> {
+        let index = self.outcome_index_checked(outcome)?;
+        Ok(self.outcomes[index].mint)
+    }
+
+    fn outcome_index_checked(&self, outcome: u8) -> Result<usize> {
+        require!(
+            outcome < self.outcome_count,
+            ContractError::InvalidOutcome
+        );
+        Ok(outcome as usize)
+    }
+
+    /// Tokens sold of each outcome, the LMSR state vector.
+    fn quantities(&self) -> Vec<u64> {
+        self.active_outcomes().iter().map(|o| o.sold).collect()
+    }
+
+    /// Pool reserves as (traded outcome, other outcome); the constant-product
+    /// curve only runs on binary markets.
+    fn binary_reserves(&self, index: usize) -> (u64, u64) {
+        (self.outcomes[index].reserve, self.outcomes[1 - index].reserve)
+    }
+
     pub fn get_signer<'a>(bump: &'a u8, market_id: &'a [u8]) -> [&'a [u8]; 3] {
         [
             MARKET_SEED.as_bytes(),
@@ -62,53 +191,377 @@ impl Market {
         ]
     }
 
+    /// Records `amount` complete sets minted against `amount` of collateral.
+    /// Every outcome's outstanding count moves by the same amount, which
+    /// leaves the curve's prices unchanged: a set costs exactly one unit.
+    pub fn split(&mut self, amount: u64) -> Result<()> {
+        for outcome in self.outcomes[..self.outcome_count as usize].iter_mut() {
+            outcome.sold = outcome
+                .sold
+                .checked_add(amount)
+                .ok_or(ContractError::ArithmeticError)?;
+        }
+        self.payout_reserve = self
+            .payout_reserve
+            .checked_add(amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Records `amount` complete sets burned for their collateral, whether
+    /// they came from `split` or from buying each outcome.
+    pub fn merge(&mut self, amount: u64) -> Result<()> {
+        for outcome in self.outcomes[..self.outcome_count as usize].iter_mut() {
+            outcome.sold = outcome
+                .sold
+                .checked_sub(amount)
+                .ok_or(ContractError::InsufficientMarketBalance)?;
+        }
+        self.payout_reserve = self
+            .payout_reserve
+            .checked_sub(amount)
+            .ok_or(ContractError::InsufficientMarketBalance)?;
+        Ok(())
+    }
+
+    /// Whether the market may be cancelled at `now`: it missed its funding
+    /// deadline, or its oracle has not resolved it within the grace period.
+    pub fn can_cancel(&self, now: i64, oracle_grace_period: i64) -> bool {
+        match self.market_status {
+            MarketStatus::Prepare => now > self.funding_deadline,
+            MarketStatus::Active => {
+                now > self.resolution_date.saturating_add(oracle_grace_period)
+            }
+            MarketStatus::Finished | MarketStatus::Cancelled => false,
+        }
+    }
+
+    /// Whether a feed update made at `timestamp` can settle the market: it
+    /// must not predate the resolution date, and must follow it within
+    /// `window` seconds.
+    pub fn in_resolution_window(&self, timestamp: i64, window: i64) -> bool {
+        timestamp >= self.resolution_date
+            && timestamp <= self.resolution_date.saturating_add(window)
+    }
+
+    /// Cancels the market and splits the payout reserve across outcomes by
+    /// the value of their outstanding tokens at the last price, so every
+    /// token refunds the same share of it however it was acquired. Crank
+    /// fees become surplus, since nobody resolves a cancelled market.
+    pub fn cancel(&mut self) -> Result<()> {
+        let decimal_multiplier = 10u128.pow(self.decimal as u32);
+        let mut value = [0u128; MAX_OUTCOMES];
+        let mut total_value = 0u128;
+        for (index, outcome) in self.active_outcomes().iter().enumerate() {
+            value[index] = outcome.sold as u128 * outcome.price as u128 / decimal_multiplier;
+            total_value += value[index];
+        }
+
+        let reserve = self.payout_reserve as u128;
+        for (index, outcome) in self.outcomes[..self.outcome_count as usize]
+            .iter_mut()
+            .enumerate()
+        {
+            // nothing outstanding leaves nothing to refund
+            outcome.payout = reserve
+                .checked_mul(value[index])
+                .ok_or(ContractError::ArithmeticError)?
+                .checked_div(total_value)
+                .unwrap_or(0) as u64;
+        }
+
+        self.take_crank_pool();
+        self.update_market_status(MarketStatus::Cancelled);
+        Ok(())
+    }
+
+    /// Signer seeds for the market PDA, e.g. to pay out of its vault.
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        Market::get_signer(&self.bump, self.market_id.as_bytes())
+    }
+
     pub fn update_market_settings(
         &mut self,
-        value: f64,
-        range: u8,
+        params: &MarketParams,
         creator: Pubkey,
         feed: Pubkey,
         token_a: Pubkey,
         token_b: Pubkey,
-        token_amount: u64,
-        token_price: u64,
-        date: i64,
+        decimal: u8,
     ) -> Result<()> {
         self.creator = creator;
-        self.value = value;
-        self.range = range;
+        self.value = params.value;
+        self.comparison = params.comparison;
+        self.kind = params.kind;
         self.feed = feed;
-        self.token_a = token_a;
-        self.token_b = token_b;
-        self.token_a_amount = token_amount;
-        self.token_b_amount = token_amount;
-        self.token_price_a = token_price;
-        self.token_price_b = token_price;
-        self.yes_amount = 1;
-        self.no_amount = 1;
-        self.resolution_date = date;
-
-        let total_token = token_amount
-            .checked_add(token_amount)
-            .ok_or(ContractError::ArithmeticError)?;
-        self.total_reserve = token_amount
-            .checked_mul(token_price)
-            .ok_or(ContractError::ArithmeticError)?
-            .checked_div(total_token)
+        self.decimal = decimal;
+        self.curve = params.curve;
+        self.liquidity_param = params.liquidity_param;
+        self.resolution_date = params.date;
+        self.funding_deadline = params.funding_deadline;
+        self.max_confidence_interval = params.max_confidence_interval;
+
+        let token_amount = params
+            .token_amount
+            .checked_mul(10u64.pow(decimal as u32))
             .ok_or(ContractError::ArithmeticError)?;
+        self.add_outcome(token_a, token_amount)?;
+        self.add_outcome(token_b, token_amount)?;
+        self.update_token_price()?;
 
         msg!("🤖token_amount 🤖 {}", token_amount);
-        msg!("🤖token_price 🤖 {}", token_price);
-        msg!("🤖total_reserve 🤖 {}", self.total_reserve);
+        msg!("🤖prices 🤖 {:?}", self.prices());
         Ok(())
     }
 
+    /// Appends an outcome with `reserve` base units of pool inventory and
+    /// returns its index. Prices must be refreshed by the caller.
+    pub fn add_outcome(&mut self, mint: Pubkey, reserve: u64) -> Result<u8> {
+        let index = self.outcome_count;
+        require!(
+            (index as usize) < MAX_OUTCOMES,
+            ContractError::TooManyOutcomes
+        );
+        self.outcomes[index as usize] = Outcome {
+            mint,
+            reserve,
+            ..Outcome::default()
+        };
+        self.outcome_count += 1;
+        Ok(index)
+    }
+
+    /// Outcome the resolved feed value settles on. Binary markets apply the
+    /// comparison, with Yes as outcome 0 and No as outcome 1; categorical
+    /// markets read the feed value itself as the winning index.
+    pub fn outcome_for(&self, observed: i128) -> Result<u8> {
+        if self.outcome_count == 2 {
+            let is_yes = self.comparison.evaluate(self.value, observed);
+            return Ok(if is_yes { 0 } else { 1 });
+        }
+
+        let index = observed / 10i128.pow(FEED_PRECISION);
+        require!(
+            index >= 0 && index < self.outcome_count as i128,
+            ContractError::InvalidOutcome
+        );
+        Ok(index as u8)
+    }
+
     pub fn update_market_status(&mut self, market_status: MarketStatus) {
         self.market_status = market_status;
     }
 
-    pub fn update_result(&mut self, result: bool) {
-        self.result = result;
+    pub fn update_result(&mut self, winning_outcome: u8) {
+        self.winning_outcome = winning_outcome;
+    }
+
+    /// Collateral, in bps of one unit, each token of an outcome settles at
+    /// when the market resolves at `observed`. Discrete markets pay a full
+    /// unit to one outcome; a scalar long (outcome 0) token pays
+    /// `(clamp(v) - low) / (high - low)` and a short (outcome 1) token pays
+    /// the rest.
+    pub fn payout_weights(&self, observed: i128) -> Result<[u16; MAX_OUTCOMES]> {
+        let mut weights = [0u16; MAX_OUTCOMES];
+        match self.kind {
+            MarketKind::Discrete => {
+                weights[self.outcome_for(observed)? as usize] = BPS_DENOMINATOR as u16;
+            }
+            MarketKind::Scalar { low, high } => {
+                let long = (observed.clamp(low, high) - low)
+                    .checked_mul(BPS_DENOMINATOR as i128)
+                    .ok_or(ContractError::ArithmeticError)?
+                    .checked_div(high - low)
+                    .ok_or(ContractError::ArithmeticError)? as u16;
+                weights[0] = long;
+                weights[1] = BPS_DENOMINATOR as u16 - long;
+            }
+        }
+        Ok(weights)
+    }
+
+    /// Settles the market: snapshots each outcome's payout at its weight times
+    /// one unit of collateral per outstanding token, records the feed value it
+    /// was decided on, and closes it to trading and liquidity. Trader funds
+    /// and LP liquidity back the payouts together, so LPs take the market
+    /// maker's profit or loss; if both fall short, payouts are scaled down.
+    pub fn resolve(&mut self, value: i128, slot: u64, timestamp: i64) -> Result<()> {
+        let weights = self.payout_weights(value)?;
+
+        let mut owed = [0u128; MAX_OUTCOMES];
+        let mut total_owed = 0u128;
+        for (index, outcome) in self.active_outcomes().iter().enumerate() {
+            owed[index] = outcome.sold as u128 * weights[index] as u128 / BPS_DENOMINATOR as u128;
+            total_owed += owed[index];
+        }
+
+        let available = self.payout_reserve as u128 + self.total_liquidity as u128;
+        let mut paid = 0u64;
+        let mut winning_outcome = 0;
+        for (index, outcome) in self.outcomes[..self.outcome_count as usize]
+            .iter_mut()
+            .enumerate()
+        {
+            outcome.payout_bps = weights[index];
+            outcome.payout = if total_owed > available {
+                (owed[index] * available / total_owed) as u64
+            } else {
+                owed[index] as u64
+            };
+            paid = paid
+                .checked_add(outcome.payout)
+                .ok_or(ContractError::ArithmeticError)?;
+            if weights[index] > weights[winning_outcome] {
+                winning_outcome = index;
+            }
+        }
+
+        // whatever the payouts leave belongs to the LPs
+        self.total_liquidity = (available - paid as u128) as u64;
+        self.payout_reserve = paid;
+        self.update_result(winning_outcome as u8);
+        self.resolved_value = value;
+        self.resolved_slot = slot;
+        self.resolved_timestamp = timestamp;
+        self.update_market_status(MarketStatus::Finished);
+        Ok(())
+    }
+
+    /// Pays out the share of an outcome's payout for `token_amount` of its
+    /// outstanding tokens and retires them, on resolution or cancellation.
+    pub fn redeem_share(&mut self, outcome: u8, token_amount: u64) -> Result<u64> {
+        let index = self.outcome_index_checked(outcome)?;
+        let outcome = &mut self.outcomes[index];
+        let sol_amount = (outcome.payout as u128)
+            .checked_mul(token_amount as u128)
+            .ok_or(ContractError::ArithmeticError)?
+            .checked_div(outcome.sold as u128)
+            .ok_or(ContractError::ArithmeticError)? as u64;
+        outcome.payout = outcome
+            .payout
+            .checked_sub(sol_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        outcome.sold = outcome
+            .sold
+            .checked_sub(token_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.payout_reserve = self
+            .payout_reserve
+            .checked_sub(sol_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(sol_amount)
+    }
+
+    /// Holds back part of a betting `fee` until the crank pool reaches
+    /// `crank_reward`, returning the part the market keeps.
+    pub fn accrue_crank_fee(&mut self, fee: u64, crank_reward: u64) -> u64 {
+        let held = fee.min(crank_reward.saturating_sub(self.crank_pool));
+        self.crank_pool += held;
+        held
+    }
+
+    /// Empties the crank pool and returns what it held.
+    pub fn take_crank_pool(&mut self) -> u64 {
+        std::mem::take(&mut self.crank_pool)
+    }
+
+    /// Collateral held above what is owed to token holders and LPs and the
+    /// rent-exempt minimum; the only part of the balance that can be swept.
+    /// `balance` is the market's lamports, or its vault amount with a zero
+    /// `rent_exempt` for token collateral.
+    pub fn surplus(&self, balance: u64, rent_exempt: u64) -> Result<u64> {
+        let owed = rent_exempt
+            .checked_add(self.payout_reserve)
+            .ok_or(ContractError::ArithmeticError)?
+            .checked_add(self.total_liquidity)
+            .ok_or(ContractError::ArithmeticError)?
+            .checked_add(self.crank_pool)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(balance.saturating_sub(owed))
+    }
+
+    /// Records a liquidity deposit and returns the LP shares it is worth.
+    pub fn add_liquidity(&mut self, amount: u64) -> Result<u64> {
+        let shares = if self.total_lp_shares == 0 {
+            amount
+        } else {
+            (amount as u128)
+                .checked_mul(self.total_lp_shares as u128)
+                .ok_or(ContractError::ArithmeticError)?
+                .checked_div(self.total_liquidity as u128)
+                .ok_or(ContractError::ArithmeticError)? as u64
+        };
+
+        self.total_liquidity = self
+            .total_liquidity
+            .checked_add(amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.total_lp_shares = self
+            .total_lp_shares
+            .checked_add(shares)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(shares)
+    }
+
+    /// Releases `shares` of the pool and returns the lamports they are worth.
+    pub fn remove_liquidity(&mut self, shares: u64) -> Result<u64> {
+        let amount = (self.total_liquidity as u128)
+            .checked_mul(shares as u128)
+            .ok_or(ContractError::ArithmeticError)?
+            .checked_div(self.total_lp_shares as u128)
+            .ok_or(ContractError::ArithmeticError)? as u64;
+
+        self.total_liquidity = self
+            .total_liquidity
+            .checked_sub(amount)
+            .ok_or(ContractError::ArithmeticError)?;
+        self.total_lp_shares = self
+            .total_lp_shares
+            .checked_sub(shares)
+            .ok_or(ContractError::ArithmeticError)?;
+        Ok(amount)
+    }
+}
+
+/// One tradable outcome of a market.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace, Debug, PartialEq)]
+pub struct Outcome {
+    pub mint: Pubkey,
+    // pool reserve, and tokens held outside the pool from buys and splits,
+    // in token base units
+    pub reserve: u64,
+    pub sold: u64,
+    // lamports per whole token at the current marginal price
+    pub price: u64,
+    pub bets: u16,
+    // set on resolution: bps of one unit each token settles at, and the
+    // collateral left to redeem for this outcome
+    pub payout_bps: u16,
+    pub payout: u64,
+}
+
+/// Pricing engine a market trades on, chosen at creation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug, PartialEq)]
+pub enum Curve {
+    ConstantProduct,
+    Lmsr,
+}
+
+/// How a market pays out. Discrete markets (binary or categorical) pay one
+/// unit per token of the winning outcome; scalar markets have a long and a
+/// short outcome whose tokens pay by where the feed lands in `[low, high]`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug, PartialEq)]
+pub enum MarketKind {
+    Discrete,
+    Scalar { low: i128, high: i128 },
+}
+
+impl MarketKind {
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            MarketKind::Scalar { low, high } => low < high,
+            MarketKind::Discrete => true,
+        }
     }
 }
 
@@ -117,15 +570,57 @@ pub enum MarketStatus {
     Prepare,
     Active,
     Finished,
+    Cancelled,
+}
+
+/// How the resolved feed value is compared to decide the market. Threshold
+/// variants compare against `Market::value`; ranges carry their own bounds,
+/// inclusive of both ends.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, Debug, PartialEq)]
+pub enum Comparison {
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+    Between { low: i128, high: i128 },
+    Outside { low: i128, high: i128 },
+}
+
+impl Comparison {
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Comparison::Between { low, high } | Comparison::Outside { low, high } => low < high,
+            _ => true,
+        }
+    }
+
+    /// Returns true when `observed` resolves the market to "Yes".
+    pub fn evaluate(&self, threshold: i128, observed: i128) -> bool {
+        match *self {
+            Comparison::GreaterThan => observed > threshold,
+            Comparison::GreaterOrEqual => observed >= threshold,
+            Comparison::LessThan => observed < threshold,
+            Comparison::LessOrEqual => observed <= threshold,
+            Comparison::Between { low, high } => observed >= low && observed <= high,
+            Comparison::Outside { low, high } => observed < low || observed > high,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct MarketParams {
-    pub value: f64,
-    pub range: u8,
+    pub value: i128,
+    pub comparison: Comparison,
+    pub kind: MarketKind,
     pub date: i64,
+    pub funding_deadline: i64,
+    pub max_confidence_interval: i128,
+    pub curve: Curve,
+    pub liquidity_param: u64,
     pub token_amount: u64,
-    pub token_price: u64,
+    // in collateral base units; SOL markets may leave it 0 for the global
+    // lamport threshold
+    pub funding_target: u64,
     pub market_id: String,
     pub name_a: Option<String>,
     pub name_b: Option<String>,
@@ -138,7 +633,244 @@ pub struct MarketParams {
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct BettingParams {
     pub market_id: String,
-    pub time: i64,
+    pub deadline: i64,
+    pub amount: u64,
+    pub outcome: u8,
+    // upper bound on the cost including fee
+    pub max_cost_lamports: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SellParams {
+    pub deadline: i64,
     pub amount: u64,
-    pub is_yes: bool,
+    pub outcome: u8,
+    // lower bound on the proceeds after fee
+    pub min_proceeds: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct OutcomeParams {
+    pub market_id: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: u64 = 1_000_000_000;
+    const DATE: i64 = 1_700_000_000;
+
+    fn market(curve: Curve, kind: MarketKind) -> Market {
+        let mut market = Market {
+            market_id: String::from("market"),
+            creator: Pubkey::default(),
+            feed: Pubkey::default(),
+            collateral_mint: None,
+            value: 100,
+            comparison: Comparison::GreaterThan,
+            kind,
+            market_status: MarketStatus::Active,
+            approved: true,
+            paused: false,
+            winning_outcome: 0,
+            decimal: 9,
+            curve,
+            liquidity_param: UNIT,
+            outcome_count: 0,
+            outcomes: [Outcome::default(); MAX_OUTCOMES],
+            payout_reserve: 0,
+            total_liquidity: 0,
+            total_lp_shares: 0,
+            crank_pool: 0,
+            funding_target: UNIT,
+            resolution_date: DATE,
+            funding_deadline: DATE - 3_600,
+            max_confidence_interval: 0,
+            resolved_value: 0,
+            resolved_slot: 0,
+            resolved_timestamp: 0,
+            bump: 255,
+        };
+        market.add_outcome(Pubkey::new_unique(), 10 * UNIT).unwrap();
+        market.add_outcome(Pubkey::new_unique(), 10 * UNIT).unwrap();
+        market.update_token_price().unwrap();
+        market
+    }
+
+    fn binary() -> Market {
+        market(Curve::ConstantProduct, MarketKind::Discrete)
+    }
+
+    /// Buys `amount` of `outcome` the way `betting` does and returns the cost.
+    fn buy(market: &mut Market, outcome: u8, amount: u64) -> u64 {
+        let cost = market.quote(amount, outcome, true).unwrap();
+        market.apply_trade(amount, cost, outcome, true).unwrap();
+        market.payout_reserve += cost;
+        cost
+    }
+
+    /// Redeems every outstanding token of every outcome and returns the total.
+    fn redeem_all(market: &mut Market) -> u64 {
+        (0..market.outcome_count)
+            .map(|outcome| {
+                let sold = market.outcomes[outcome as usize].sold;
+                if sold == 0 {
+                    return 0;
+                }
+                market.redeem_share(outcome, sold).unwrap()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn resolve_pays_one_unit_per_winning_token_and_lps_take_the_rest() {
+        let mut market = binary();
+        market.total_liquidity = 5 * UNIT;
+        let yes_cost = buy(&mut market, 0, UNIT);
+        let no_cost = buy(&mut market, 1, 2 * UNIT);
+
+        market.resolve(200, 1, DATE).unwrap();
+
+        assert_eq!(market.market_status, MarketStatus::Finished);
+        assert_eq!(market.winning_outcome, 0);
+        assert_eq!(market.outcomes[0].payout, UNIT);
+        assert_eq!(market.outcomes[1].payout, 0);
+        assert_eq!(market.payout_reserve, UNIT);
+        assert_eq!(market.total_liquidity, 5 * UNIT + yes_cost + no_cost - UNIT);
+    }
+
+    #[test]
+    fn resolve_scales_payouts_down_when_funds_fall_short() {
+        let mut market = binary();
+        market.outcomes[0].sold = 4 * UNIT;
+        market.payout_reserve = UNIT;
+        market.total_liquidity = UNIT;
+
+        market.resolve(200, 1, DATE).unwrap();
+
+        assert_eq!(market.outcomes[0].payout, 2 * UNIT);
+        assert_eq!(market.total_liquidity, 0);
+    }
+
+    #[test]
+    fn scalar_weights_split_one_unit_by_where_the_value_lands() {
+        let market = market(
+            Curve::ConstantProduct,
+            MarketKind::Scalar { low: 0, high: 100 },
+        );
+        let weights = |observed| market.payout_weights(observed).unwrap();
+        assert_eq!(weights(25)[..2], [2_500, 7_500]);
+        assert_eq!(weights(-50)[..2], [0, 10_000]);
+        assert_eq!(weights(150)[..2], [10_000, 0]);
+    }
+
+    #[test]
+    fn redeem_share_pays_pro_rata_and_never_more_than_the_payout() {
+        let mut market = binary();
+        market.outcomes[0].sold = 3;
+        market.outcomes[0].payout = 10;
+        market.payout_reserve = 10;
+
+        let paid: Vec<u64> = (0..3).map(|_| market.redeem_share(0, 1).unwrap()).collect();
+
+        assert_eq!(paid, [3, 3, 4]);
+        assert_eq!(market.payout_reserve, 0);
+        assert!(market.redeem_share(0, 1).is_err());
+    }
+
+    #[test]
+    fn cancel_refunds_every_outstanding_token_out_of_the_reserve() {
+        let mut market = binary();
+        buy(&mut market, 0, 3 * UNIT);
+        buy(&mut market, 1, UNIT);
+        market.split(UNIT).unwrap();
+        market.crank_pool = 7;
+        let reserve = market.payout_reserve;
+
+        market.cancel().unwrap();
+        assert_eq!(market.market_status, MarketStatus::Cancelled);
+        assert_eq!(market.crank_pool, 0);
+
+        // a token refunds once, whoever holds it, and refunds share the
+        // reserve instead of draining it first come first served
+        let refunded = redeem_all(&mut market);
+        assert!(refunded <= reserve);
+        assert!(reserve - refunded <= market.outcome_count as u64);
+        assert!(market.redeem_share(0, 1).is_err());
+    }
+
+    #[test]
+    fn cancel_with_nothing_outstanding_owes_nothing() {
+        let mut market = binary();
+        market.market_status = MarketStatus::Prepare;
+
+        market.cancel().unwrap();
+
+        assert!(market.active_outcomes().iter().all(|o| o.payout == 0));
+    }
+
+    #[test]
+    fn split_and_merge_move_every_outcome_and_keep_prices() {
+        let mut market = market(Curve::Lmsr, MarketKind::Discrete);
+        buy(&mut market, 0, UNIT);
+        let prices = market.prices();
+
+        market.split(2 * UNIT).unwrap();
+        market.update_token_price().unwrap();
+        assert_eq!(market.outcomes[0].sold, 3 * UNIT);
+        assert_eq!(market.outcomes[1].sold, 2 * UNIT);
+        for (before, after) in prices.iter().zip(market.prices()) {
+            assert!(before.abs_diff(after) <= 1);
+        }
+
+        market.merge(2 * UNIT).unwrap();
+        assert_eq!(market.outcomes[1].sold, 0);
+        // one outcome has none left, so no further set can be merged
+        assert!(market.merge(1).is_err());
+    }
+
+    #[test]
+    fn active_markets_cancel_only_after_the_grace_period() {
+        let mut market = binary();
+        let grace = 600;
+        assert!(!market.can_cancel(DATE + grace, grace));
+        assert!(market.can_cancel(DATE + grace + 1, grace));
+
+        market.market_status = MarketStatus::Prepare;
+        assert!(!market.can_cancel(market.funding_deadline, grace));
+        assert!(market.can_cancel(market.funding_deadline + 1, grace));
+
+        market.market_status = MarketStatus::Finished;
+        assert!(!market.can_cancel(i64::MAX, grace));
+    }
+
+    #[test]
+    fn resolution_window_opens_at_the_resolution_date() {
+        let market = binary();
+        let window = 300;
+        assert!(!market.in_resolution_window(DATE - 1, window));
+        assert!(market.in_resolution_window(DATE, window));
+        assert!(market.in_resolution_window(DATE + window, window));
+        assert!(!market.in_resolution_window(DATE + window + 1, window));
+    }
+
+    #[test]
+    fn crank_fees_stop_at_the_reward_and_are_not_surplus() {
+        let mut market = binary();
+        assert_eq!(market.accrue_crank_fee(60, 100), 60);
+        assert_eq!(market.accrue_crank_fee(60, 100), 40);
+        assert_eq!(market.accrue_crank_fee(60, 100), 0);
+
+        market.payout_reserve = 1_000;
+        market.total_liquidity = 2_000;
+        assert_eq!(market.surplus(3_150, 10).unwrap(), 40);
+        assert_eq!(market.surplus(3_000, 10).unwrap(), 0);
+
+        assert_eq!(market.take_crank_pool(), 100);
+        assert_eq!(market.surplus(3_150, 10).unwrap(), 140);
+    }
 }
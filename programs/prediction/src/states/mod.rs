@@ -1,2 +1,3 @@
 pub mod global;
+pub mod liquidity_position;
 pub mod market;
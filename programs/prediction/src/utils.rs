@@ -1,5 +1,29 @@
-use anchor_lang::{prelude::*, solana_program};
-use anchor_spl::token;
+use crate::constants::{BPS_DENOMINATOR, FEED_PRECISION};
+use crate::errors::ContractError;
+use crate::states::market::{Curve, Market};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{self, program::invoke_signed},
+    system_program::{create_account, CreateAccount},
+};
+use anchor_spl::{
+    metadata::{
+        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            metadata_pointer, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        onchain::invoke_transfer_checked,
+        state::Mint as MintState,
+    },
+    token_interface::{
+        self, Burn, InitializeMint2, Mint, MintTo, TokenAccount, TransferChecked,
+    },
+};
+use rust_decimal::Decimal;
+use spl_token_metadata_interface::state::TokenMetadata;
 
 pub fn sol_transfer<'a>(
     from_account: AccountInfo<'a>,
@@ -23,6 +47,7 @@ pub fn sol_transfer<'a>(
 pub fn token_transfer<'a>(
     from_ata: AccountInfo<'a>,
     to_ata: AccountInfo<'a>,
+    mint: &InterfaceAccount<'a, Mint>,
     authority: AccountInfo<'a>,
     token_program: AccountInfo<'a>,
     singer_seed: &[&[&[u8]]; 1],
@@ -30,14 +55,378 @@ pub fn token_transfer<'a>(
 ) -> Result<()> {
     let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
         token_program.to_account_info(),
-        token::Transfer {
+        TransferChecked {
             from: from_ata,
+            mint: mint.to_account_info(),
             authority: authority.to_account_info(),
             to: to_ata,
         },
         singer_seed,
     );
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+    Ok(())
+}
+
+/// The pool's (mint, token account) pair for each outcome of a
+/// constant-product market, traded outcome first, checking the other
+/// outcome's mint against the market. Other curves keep no complete sets in
+/// the pool and get `None`.
+pub fn cpmm_pool<'a>(
+    market: &Market,
+    outcome: u8,
+    traded: (AccountInfo<'a>, AccountInfo<'a>),
+    other_mint: Option<AccountInfo<'a>>,
+    other_account: Option<AccountInfo<'a>>,
+) -> Result<Option<[(AccountInfo<'a>, AccountInfo<'a>); 2]>> {
+    if market.curve != Curve::ConstantProduct {
+        return Ok(None);
+    }
+    let other_mint = other_mint.ok_or(ContractError::MissingPoolAccount)?;
+    let other_account = other_account.ok_or(ContractError::MissingPoolAccount)?;
+    require_keys_eq!(
+        other_mint.key(),
+        market.outcome_mint(1 - outcome)?,
+        ContractError::InvalidMint
+    );
+    Ok(Some([traded, (other_mint, other_account)]))
+}
+
+/// Mints `amount` complete sets into the pool, or burns them back out, so
+/// the pool's token balances stay equal to the constant-product reserves.
+pub fn pool_complete_sets<'a>(
+    pool: [(AccountInfo<'a>, AccountInfo<'a>); 2],
+    market: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    signer_seed: &[&[&[u8]]; 1],
+    amount: u64,
+    is_mint: bool,
+) -> Result<()> {
+    for (mint, account) in pool {
+        if is_mint {
+            token_interface::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    MintTo {
+                        mint,
+                        to: account,
+                        authority: market.clone(),
+                    },
+                    signer_seed,
+                ),
+                amount,
+            )?;
+        } else {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    Burn {
+                        mint,
+                        from: account,
+                        authority: market.clone(),
+                    },
+                    signer_seed,
+                ),
+                amount,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Token accounts a collateral transfer moves between when the market's
+/// collateral is an SPL token rather than native SOL.
+pub struct TokenLeg<'a, 'b> {
+    pub from: AccountInfo<'a>,
+    pub to: AccountInfo<'a>,
+    pub mint: &'b InterfaceAccount<'a, Mint>,
+    pub token_program: AccountInfo<'a>,
+    // extra accounts a Token-2022 transfer hook on the mint needs
+    pub hook_accounts: &'b [AccountInfo<'a>],
+}
 
+/// Builds the token leg of a collateral transfer, requiring both token
+/// accounts when the market has a collateral mint.
+pub fn token_leg<'a, 'b>(
+    collateral_mint: Option<Pubkey>,
+    mint: Option<&'b InterfaceAccount<'a, Mint>>,
+    from: Option<AccountInfo<'a>>,
+    to: Option<AccountInfo<'a>>,
+    token_program: AccountInfo<'a>,
+    hook_accounts: &'b [AccountInfo<'a>],
+) -> Result<Option<TokenLeg<'a, 'b>>> {
+    if collateral_mint.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(TokenLeg {
+        from: from.ok_or(ContractError::MissingCollateralAccount)?,
+        to: to.ok_or(ContractError::MissingCollateralAccount)?,
+        mint: mint.ok_or(ContractError::MissingCollateralAccount)?,
+        token_program,
+        hook_accounts,
+    }))
+}
+
+/// Moves collateral along `leg`, resolving the mint's transfer hook accounts
+/// from `leg.hook_accounts` when it has one.
+fn leg_transfer<'a>(
+    leg: TokenLeg<'a, '_>,
+    authority: AccountInfo<'a>,
+    signer_seed: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    invoke_transfer_checked(
+        leg.token_program.key,
+        leg.from,
+        leg.mint.to_account_info(),
+        leg.to,
+        authority,
+        leg.hook_accounts,
+        amount,
+        leg.mint.decimals,
+        signer_seed,
+    )?;
     Ok(())
 }
+
+/// Token-2022 mint extensions that leave the vault balance the market books
+/// untouched. Anything else, such as a transfer fee, a permanent delegate, a
+/// close authority or frozen default accounts, can move or lock its funds.
+const COLLATERAL_EXTENSIONS: [ExtensionType; 3] = [
+    ExtensionType::TransferHook,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+/// Rejects collateral mints with any extension outside
+/// `COLLATERAL_EXTENSIONS`.
+pub fn check_collateral_mint(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    require!(
+        state
+            .get_extension_types()?
+            .iter()
+            .all(|extension| COLLATERAL_EXTENSIONS.contains(extension)),
+        ContractError::UnsupportedCollateralExtension
+    );
+    Ok(())
+}
+
+/// Pays collateral from a signer: lamports from `payer` to `to`, or tokens
+/// along `token` with `payer` as authority.
+pub fn collateral_in<'a>(
+    payer: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    token: Option<TokenLeg<'a, '_>>,
+    system_program: AccountInfo<'a>,
+    amount: u64,
+) -> Result<()> {
+    match token {
+        None => sol_transfer(payer, to, system_program, amount),
+        Some(leg) => leg_transfer(leg, payer, &[], amount),
+    }
+}
+
+/// Pays collateral out of the market: lamports from `market` to `to`, or
+/// tokens from the vault along `token`, signed by the market.
+pub fn collateral_out<'a>(
+    market: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    token: Option<TokenLeg<'a, '_>>,
+    signer_seed: &[&[&[u8]]; 1],
+    amount: u64,
+) -> Result<()> {
+    match token {
+        None => {
+            market.sub_lamports(amount)?;
+            to.add_lamports(amount)?;
+            Ok(())
+        }
+        Some(leg) => leg_transfer(leg, market, signer_seed, amount),
+    }
+}
+
+/// Returns the market's collateral balance and the part of it reserved for
+/// rent. Token collateral sits in the vault, which carries its own rent.
+pub fn collateral_balance(
+    market: &AccountInfo,
+    collateral_mint: Option<Pubkey>,
+    vault: Option<&InterfaceAccount<TokenAccount>>,
+) -> Result<(u64, u64)> {
+    match (collateral_mint, vault) {
+        (None, _) => Ok((
+            market.lamports(),
+            Rent::get()?.minimum_balance(market.data_len()),
+        )),
+        (Some(_), Some(vault)) => Ok((vault.amount, 0)),
+        (Some(_), None) => err!(ContractError::MissingCollateralAccount),
+    }
+}
+
+/// Accounts needed to create an outcome mint with the market as its mint
+/// and metadata authority.
+pub struct OutcomeMintAccounts<'a> {
+    pub payer: AccountInfo<'a>,
+    pub mint: AccountInfo<'a>,
+    pub market: AccountInfo<'a>,
+    // Metaplex metadata account, only used for legacy token mints
+    pub metadata: Option<AccountInfo<'a>>,
+    pub token_program: AccountInfo<'a>,
+    pub token_metadata_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub rent: AccountInfo<'a>,
+}
+
+/// Creates an outcome mint at its PDA under either token program. Token-2022
+/// mints carry their name, symbol and uri in the mint itself through the
+/// metadata-pointer and token-metadata extensions; legacy mints get a
+/// separate Metaplex metadata account.
+pub fn create_outcome_mint<'a>(
+    accounts: OutcomeMintAccounts<'a>,
+    name: String,
+    symbol: String,
+    uri: String,
+    decimals: u8,
+    mint_seed: &[&[&[u8]]; 1],
+    market_seed: &[&[&[u8]]; 1],
+) -> Result<()> {
+    let is_token_2022 = accounts.token_program.key() == spl_token_2022::ID;
+    let extensions = if is_token_2022 {
+        vec![ExtensionType::MetadataPointer]
+    } else {
+        vec![]
+    };
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)?;
+
+    // token metadata reallocs the mint, so fund the final size up front
+    let token_metadata = TokenMetadata {
+        update_authority: Some(accounts.market.key()).try_into()?,
+        mint: accounts.mint.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        ..Default::default()
+    };
+    let metadata_len = if is_token_2022 {
+        token_metadata.tlv_size_of()?
+    } else {
+        0
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata_len);
+
+    create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.clone(),
+            CreateAccount {
+                from: accounts.payer.clone(),
+                to: accounts.mint.clone(),
+            },
+            mint_seed,
+        ),
+        lamports,
+        space as u64,
+        accounts.token_program.key,
+    )?;
+
+    if is_token_2022 {
+        solana_program::program::invoke(
+            &metadata_pointer::instruction::initialize(
+                accounts.token_program.key,
+                accounts.mint.key,
+                Some(accounts.market.key()),
+                Some(accounts.mint.key()),
+            )?,
+            &[accounts.mint.clone(), accounts.token_program.clone()],
+        )?;
+    }
+
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            InitializeMint2 {
+                mint: accounts.mint.clone(),
+            },
+        ),
+        decimals,
+        accounts.market.key,
+        None,
+    )?;
+
+    if is_token_2022 {
+        invoke_signed(
+            &spl_token_metadata_interface::instruction::initialize(
+                accounts.token_program.key,
+                accounts.mint.key,
+                accounts.market.key,
+                accounts.mint.key,
+                accounts.market.key,
+                name,
+                symbol,
+                uri,
+            ),
+            &[
+                accounts.mint.clone(),
+                accounts.market.clone(),
+                accounts.token_program.clone(),
+            ],
+            market_seed,
+        )?;
+        return Ok(());
+    }
+
+    let metadata = accounts
+        .metadata
+        .ok_or(ContractError::MissingMetadataAccount)?;
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program,
+            CreateMetadataAccountsV3 {
+                payer: accounts.payer,
+                mint: accounts.mint,
+                metadata,
+                update_authority: accounts.market.clone(),
+                mint_authority: accounts.market,
+                system_program: accounts.system_program,
+                rent: accounts.rent,
+            },
+            market_seed,
+        ),
+        DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        false,
+        true,
+        None,
+    )?;
+    Ok(())
+}
+
+pub fn calc_fee(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(ContractError::ArithmeticError)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(ContractError::ArithmeticError)?;
+    Ok(fee as u64)
+}
+
+/// Converts a feed decimal into an integer scaled by `FEED_PRECISION`.
+pub fn to_feed_precision(value: Decimal) -> Result<i128> {
+    let mut scaled = value;
+    scaled.rescale(FEED_PRECISION);
+    require!(
+        scaled.scale() == FEED_PRECISION,
+        ContractError::ArithmeticError
+    );
+    Ok(scaled.mantissa())
+}
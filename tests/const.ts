@@ -1,12 +1,15 @@
 import { PublicKey } from "@solana/web3.js";
 
-export const GLOBAL_SEED = "global_7";
-export const MARKET_SEED = "market_7";
-export const MINT_SEED_A = "mint_a_7";
-export const MINT_SEED_B = "mint_b_7";
+export const GLOBAL_SEED = "global_seed";
+export const MARKET_SEED = "market_seed";
+export const MINT_SEED_A = "mint_a_seed";
+export const MINT_SEED_B = "mint_b_seed";
+export const LIQUIDITY_SEED = "liquidity_seed";
 
-export const PREDICTION_ID = new PublicKey("Da4udDYBTFUrdCjRkstWMMpxQdPi6pkq7CXicSUNZA2u");
+export const PREDICTION_ID = new PublicKey("Bki3CWk4AmVF78zvh81rup2EK2iJY4WRCUXesAv8TECF");
 export const SOL_USDC_FEED = "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR";
+// switchboard on-demand pull feed the test market resolves on
+export const PULL_FEED = new PublicKey("5mXfTYitRFsWPhdJfp2fc8N6hK8cw6NB5jAYpronQasj");
 export const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 export const tokenA = new PublicKey("GYwonCewvmEKtMY7ELqGfZGM4Y1egwGaU28dNnY6UnJL");
 export const tokenB = new PublicKey("AsVh2yGE2Xg1Uqmnwcwb6MB36VCTHDat4QuUpF1jPFcw");
@@ -2,8 +2,8 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Prediction } from "../target/types/prediction";
 import { PublicKey, SystemProgram, Keypair, Transaction, Connection, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { GLOBAL_SEED, PREDICTION_ID, SOL_USDC_FEED, MARKET_SEED, TOKEN_METADATA_PROGRAM_ID, tokenAAmount, tokenBAmount, feeAuthority, METADATA_SEED, MINT_SEED_A, MINT_SEED_B } from "./const";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, getMint } from "@solana/spl-token";
+import { GLOBAL_SEED, PREDICTION_ID, PULL_FEED, MARKET_SEED, LIQUIDITY_SEED, TOKEN_METADATA_PROGRAM_ID, tokenAAmount, feeAuthority, METADATA_SEED, MINT_SEED_A, MINT_SEED_B } from "./const";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { getOrCreateATAInstruction, getAssociatedTokenAccount } from "./utils";
 import BN from "bn.js";
 let owner: Keypair;
@@ -11,6 +11,8 @@ let tokenA: PublicKey;
 let tokenB: PublicKey;
 let provider: anchor.AnchorProvider;
 let market: PublicKey;
+let global: PublicKey;
+const marketId = `test_${Date.now()}`;
 const connection = new Connection("https://api.devnet.solana.com", "confirmed");
 
 describe("prediction", () => {
@@ -20,7 +22,8 @@ describe("prediction", () => {
   provider = anchor.AnchorProvider.env();
   const program = anchor.workspace.Prediction as Program<Prediction>;
   before(async () => {
-    market = PublicKey.findProgramAddressSync([Buffer.from(MARKET_SEED), owner.publicKey.toBuffer()], PREDICTION_ID)[0];
+    global = PublicKey.findProgramAddressSync([Buffer.from(GLOBAL_SEED)], PREDICTION_ID)[0];
+    market = PublicKey.findProgramAddressSync([Buffer.from(MARKET_SEED), Buffer.from(marketId)], PREDICTION_ID)[0];
     tokenA = PublicKey.findProgramAddressSync(
       [Buffer.from(MINT_SEED_A), market.toBuffer()],
       PREDICTION_ID
@@ -32,7 +35,7 @@ describe("prediction", () => {
     console.log("market ====>", market);
     console.log("tokenA ====>", tokenA);
     console.log("tokenB ====>", tokenB);
-    
+
     const OracleEvent = await program.addEventListener("OracleResUpdated", (event, slot, signature) => {
       console.log("👻OracleResUpdated 👻", Number(event.oracleRes));
     });
@@ -46,21 +49,22 @@ describe("prediction", () => {
     });
 
   });
-  
+
   it("Is initialized!", async () => {
-    const global = PublicKey.findProgramAddressSync([Buffer.from(GLOBAL_SEED)], PREDICTION_ID)[0];
     console.log("global ====>", global);
 
-    // Add your test here.
+    // the payer stays admin and holds every role
     const tx = await program.methods.initialize({
       feeAuthority: feeAuthority,
       creatorFeeAmount: new BN(0.001 * 10 ** 9),
-      liqudityUserFeeAmount: new BN(0.001 * 10 ** 9),
-      bettingUserFeeAmount: new BN(0.001 * 10 ** 9),
       marketCount: new BN(0.1 * 10 ** 9),
       decimal: 9,
-      feePercentage: 10,
-    }).accounts({
+      bettingFeeBps: 100,
+      fundFeeBps: 100,
+      oracleGracePeriod: new BN(24 * 60 * 60),
+      resolutionWindow: new BN(60 * 60),
+      crankReward: new BN(0.0001 * 10 ** 9),
+    }, null).accounts({
       global,
       payer: owner.publicKey,
       systemProgram: SystemProgram.programId,
@@ -71,8 +75,6 @@ describe("prediction", () => {
   });
 
   it("Create market", async () => {
-    const global = PublicKey.findProgramAddressSync([Buffer.from(GLOBAL_SEED)], PREDICTION_ID)[0];
-    console.log("global in creating market ====>", global);
     const globalAccount = await program.account.global.fetch(global);
     console.log("🎫globalAccount in creating market  🎫", globalAccount);
 
@@ -96,12 +98,22 @@ describe("prediction", () => {
       ],
       TOKEN_METADATA_PROGRAM_ID
     )[0];
-    
+
     // Create market //////////////////////////////////////////////////////////////////////////////////////////////////////////////
+    // resolves yes if the feed is above 190 (feed values are scaled by 10^18)
+    const now = Math.floor(Date.now() / 1000);
     const tx = await program.methods.initMarket({
-      quest: 190,
+      value: new BN(190).mul(new BN(10).pow(new BN(18))),
+      comparison: { greaterThan: {} },
+      kind: { discrete: {} },
+      date: new BN(now + 60 * 60),
+      fundingDeadline: new BN(now + 30 * 60),
+      maxConfidenceInterval: new BN(10).pow(new BN(18)),
+      curve: { constantProduct: {} },
+      liquidityParam: new BN(0),
       tokenAmount: new BN(tokenAAmount),
-      tokenPrice: new BN(0.00005 * 10 ** 9),
+      fundingTarget: new BN(0),
+      marketId,
       nameA: "tokenA",
       nameB: "tokenB",
       symbolA: "tokenA",
@@ -112,14 +124,16 @@ describe("prediction", () => {
       user: owner.publicKey,
       feeAuthority: feeAuthority,
       market,
-      global,
-      feed: new PublicKey("5mXfTYitRFsWPhdJfp2fc8N6hK8cw6NB5jAYpronQasj"),
+      globalPda: global,
+      feed: PULL_FEED,
       metadataA: metadata_a,
       metadataB: metadata_b,
       tokenMintA: tokenA,
       tokenMintB: tokenB,
-      // pdaTokenAAccount,
-      // pdaTokenBAccount,
+      // SOL collateral
+      collateralMint: null,
+      collateralVault: null,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
@@ -127,7 +141,7 @@ describe("prediction", () => {
       systemProgram: SystemProgram.programId,
     }).transaction();
 
-    const mintTx = await program.methods.mintToken().accounts({
+    const mintTx = await program.methods.mintToken(marketId).accounts({
       pdaTokenAAccount,
       pdaTokenBAccount,
       user: owner.publicKey,
@@ -145,9 +159,17 @@ describe("prediction", () => {
       systemProgram: SystemProgram.programId,
     }).transaction();
 
+    // the owner is also the market curator
+    const approveTx = await program.methods.approveMarket().accounts({
+      curator: owner.publicKey,
+      market,
+      global,
+    }).transaction();
+
     const creatTx = new Transaction();
     creatTx.add(tx);
     creatTx.add(mintTx);
+    creatTx.add(approveTx);
     creatTx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
     creatTx.feePayer = owner.publicKey;
 
@@ -164,17 +186,24 @@ describe("prediction", () => {
   });
 
   it("Deposit liquidity", async () => {
-    const global = PublicKey.findProgramAddressSync([Buffer.from(GLOBAL_SEED)], PREDICTION_ID)[0];
-    console.log("global in creating market ====>", global);
-    const market = PublicKey.findProgramAddressSync([Buffer.from(MARKET_SEED), owner.publicKey.toBuffer()], PREDICTION_ID)[0];
+    const liquidityPosition = PublicKey.findProgramAddressSync(
+      [Buffer.from(LIQUIDITY_SEED), market.toBuffer(), owner.publicKey.toBuffer()],
+      PREDICTION_ID
+    )[0];
 
+    // funding the global target activates the market
     let tx = await program.methods.addLiquidity(new BN(0.1 * 10 ** 9))
     .accounts({
       user: owner.publicKey,
-      creator: owner.publicKey,
       feeAuthority: feeAuthority,
       market,
+      liquidityPosition,
       global,
+      collateralMint: null,
+      collateralVault: null,
+      userCollateralAccount: null,
+      feeCollateralAccount: null,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
     }).transaction();
 
@@ -193,29 +222,36 @@ describe("prediction", () => {
     const confirm = await connection.confirmTransaction(sig, "confirmed");
     console.log("🤖deposit liquidity transaction signature 🤖", sig);
   });
-  
+
   it("It is betting", async () => {
-    const global = PublicKey.findProgramAddressSync([Buffer.from(GLOBAL_SEED)], PREDICTION_ID)[0];
-    console.log("global in creating market ====>", global);
-    const market = PublicKey.findProgramAddressSync([Buffer.from(MARKET_SEED), owner.publicKey.toBuffer()], PREDICTION_ID)[0];
     let pdaTokenAAccount = await getAssociatedTokenAccount(market, tokenA);
-    // let pdaTokenBAccount = await getAssociatedTokenAccount(market, tokenB);
-    // let userTokenBAccount = getAssociatedTokenAddressSync(tokenB, owner.publicKey);
+    let pdaTokenBAccount = await getAssociatedTokenAccount(market, tokenB);
 
     let [userTokenAAccount, create_ata_instruction] = await getOrCreateATAInstruction(tokenA, owner.publicKey, connection );
 
+    // buy outcome 0 (tokenA); the constant-product pool also needs tokenB
     const tx = await program.methods.createBet({
+      marketId,
+      deadline: new BN(Math.floor(Date.now() / 1000) + 60),
       amount: new BN(10000),
-      isYes: true,
+      outcome: 0,
+      maxCostLamports: new BN(0.01 * 10 ** 9),
     }).accounts({
       user: owner.publicKey,
       creator: owner.publicKey,
       tokenMint: tokenA,
       pdaTokenAccount: pdaTokenAAccount,
+      otherTokenMint: tokenB,
+      pdaOtherTokenAccount: pdaTokenBAccount,
       userTokenAccount: userTokenAAccount,
       feeAuthority: feeAuthority,
       market,
       global,
+      collateralMint: null,
+      collateralVault: null,
+      userCollateralAccount: null,
+      feeCollateralAccount: null,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
@@ -238,17 +274,20 @@ describe("prediction", () => {
     });
 
     const confirm = await connection.confirmTransaction(sig, "confirmed");
-    console.log("🤖Add liquidity transaction signature 🤖", sig);
+    console.log("🤖create betting transaction signature 🤖", sig);
   });
 
   it("Get oracle res", async () => {
-    const market = PublicKey.findProgramAddressSync([Buffer.from(MARKET_SEED), owner.publicKey.toBuffer()], PREDICTION_ID)[0];
-
+    // only succeeds once the market's resolution date has passed
     const tx = await program.methods.getRes().accounts({
       user: owner.publicKey,
       market,
-      feedAggregator: new PublicKey(SOL_USDC_FEED),
-      feed: new PublicKey("5mXfTYitRFsWPhdJfp2fc8N6hK8cw6NB5jAYpronQasj"),
+      global,
+      feed: PULL_FEED,
+      collateralMint: null,
+      collateralVault: null,
+      userCollateralAccount: null,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
     }).signers([owner]).rpc();
 